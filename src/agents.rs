@@ -0,0 +1,233 @@
+//! Agent-specific entry files
+//!
+//! Each AI coding agent looks for its instructions in its own file at a fixed
+//! location. This module generates those entry files, either pointing at the
+//! generated documentation (for agents that support file imports) or inlining
+//! the core sections (for agents that do not).
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::managed::{self, FileChange};
+use crate::templates;
+
+/// An AI coding agent with its own instructions file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Agent {
+    /// Claude Code (CLAUDE.md)
+    Claude,
+    /// Codex, opencode and other agents reading AGENTS.md
+    Agents,
+    /// Gemini CLI (GEMINI.md)
+    Gemini,
+    /// GitHub Copilot (.github/copilot-instructions.md)
+    Copilot,
+    /// Cursor (.cursor/rules/proact.mdc)
+    Cursor,
+    /// All of the above
+    All,
+}
+
+impl Agent {
+    /// Every concrete agent, in generation order
+    pub const ALL: [Agent; 5] = [
+        Agent::Claude,
+        Agent::Agents,
+        Agent::Gemini,
+        Agent::Copilot,
+        Agent::Cursor,
+    ];
+
+    /// Path of the entry file relative to the target project root
+    pub fn entry_path(self) -> &'static str {
+        match self {
+            Agent::Claude => "CLAUDE.md",
+            Agent::Agents => "AGENTS.md",
+            Agent::Gemini => "GEMINI.md",
+            Agent::Copilot => ".github/copilot-instructions.md",
+            Agent::Cursor => ".cursor/rules/proact.mdc",
+            Agent::All => "",
+        }
+    }
+}
+
+/// Entry files written into each workspace package for the requested agents
+///
+/// Only agents that read instructions from nested directories get
+/// per-package files; AGENTS.md is used when none of them is requested.
+pub fn package_entry_paths(agents: &[Agent]) -> Vec<&'static str> {
+    let paths: Vec<&'static str> = resolve(agents)
+        .into_iter()
+        .filter(|agent| matches!(agent, Agent::Claude | Agent::Agents | Agent::Gemini))
+        .map(Agent::entry_path)
+        .collect();
+    if paths.is_empty() {
+        vec![Agent::Agents.entry_path()]
+    } else {
+        paths
+    }
+}
+
+/// Expand `all` and remove duplicates, keeping generation order stable
+pub fn resolve(agents: &[Agent]) -> Vec<Agent> {
+    if agents.contains(&Agent::All) {
+        return Agent::ALL.to_vec();
+    }
+    Agent::ALL
+        .into_iter()
+        .filter(|agent| agents.contains(agent))
+        .collect()
+}
+
+/// Plan entry files for the requested agents
+///
+/// `docs_dir` is the generated documentation directory relative to the
+/// target root (e.g. `docs`), used for links and imports.
+pub fn plan_agent_files(
+    target_path: &Path,
+    docs_dir: &Path,
+    agents: &[Agent],
+) -> Result<Vec<FileChange>> {
+    let docs = docs_dir.display().to_string();
+    let mut changes = Vec::new();
+
+    for agent in resolve(agents) {
+        let path = target_path.join(agent.entry_path());
+
+        let change = match agent {
+            Agent::Claude => {
+                let content = import_entry("Claude Code", &docs);
+                managed::plan_managed(&path, &content)?
+            }
+            Agent::Gemini => {
+                let content = import_entry("Gemini CLI", &docs);
+                managed::plan_managed(&path, &content)?
+            }
+            Agent::Agents | Agent::Copilot => {
+                let content = inline_entry(&docs);
+                managed::plan_managed(&path, &content)?
+            }
+            Agent::Cursor => {
+                // Cursor requires front matter at the top of the file, so the
+                // rule file is owned by proact entirely instead of using markers
+                let content = cursor_rule(&docs);
+                managed::plan_file(&path, &content)?
+            }
+            Agent::All => unreachable!("resolve() expands Agent::All"),
+        };
+        changes.push(change);
+    }
+
+    Ok(changes)
+}
+
+/// Entry file for agents that support `@path` imports
+fn import_entry(agent_name: &str, docs: &str) -> String {
+    format!(
+        r#"# {agent_name} Instructions
+
+This project's AI agent guidelines are generated by Proact. Follow them for
+every task:
+
+@{docs}/ai_agent_instructions.md
+@{docs}/process.md
+@{docs}/tools.md
+@{docs}/learnings.md
+
+Record new issues and their resolutions in `{docs}/learnings.md` with
+`proact learn --title ... --issue ... --resolution ... --prevention ...`.
+"#
+    )
+}
+
+/// Entry file for agents that read a single markdown file without imports
+fn inline_entry(docs: &str) -> String {
+    format!(
+        "{}\n\n{}\n\n{}\n",
+        templates::process_guidelines(),
+        templates::quality_standards(),
+        references(docs)
+    )
+}
+
+/// Cursor project rule with front matter that applies it to every request
+fn cursor_rule(docs: &str) -> String {
+    format!(
+        r#"---
+description: Project development guidelines generated by Proact
+globs:
+alwaysApply: true
+---
+
+{}
+"#,
+        inline_entry(docs).trim_end()
+    )
+}
+
+/// List of the generated documents for agents that cannot import them
+fn references(docs: &str) -> String {
+    format!(
+        r#"## Further Reading
+
+Read these generated documents before making changes:
+
+- `{docs}/ai_agent_instructions.md` - full AI agent guidelines
+- `{docs}/process.md` - development process and pre-commit steps
+- `{docs}/tools.md` - available development tools
+- `{docs}/learnings.md` - past issues and how to prevent them"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_expands_all() {
+        assert_eq!(resolve(&[Agent::All]), Agent::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_resolve_keeps_order_and_dedupes() {
+        assert_eq!(
+            resolve(&[Agent::Cursor, Agent::Claude, Agent::Cursor]),
+            vec![Agent::Claude, Agent::Cursor]
+        );
+    }
+
+    #[test]
+    fn test_package_entry_paths() {
+        assert_eq!(package_entry_paths(&[]), ["AGENTS.md"]);
+        assert_eq!(package_entry_paths(&[Agent::Cursor]), ["AGENTS.md"]);
+        assert_eq!(
+            package_entry_paths(&[Agent::All]),
+            ["CLAUDE.md", "AGENTS.md", "GEMINI.md"]
+        );
+    }
+
+    #[test]
+    fn test_import_entry_uses_docs_dir() {
+        let content = import_entry("Claude Code", "documentation");
+        assert!(content.contains("@documentation/ai_agent_instructions.md"));
+        assert!(content.contains("@documentation/learnings.md"));
+    }
+
+    #[test]
+    fn test_inline_entry_contains_sections() {
+        let content = inline_entry("docs");
+        assert!(content.contains("Process-Oriented Workflow"));
+        assert!(content.contains("Quality-Oriented Development"));
+        assert!(content.contains("`docs/process.md`"));
+    }
+
+    #[test]
+    fn test_cursor_rule_has_front_matter() {
+        let content = cursor_rule("docs");
+        assert!(content.starts_with("---\ndescription: "));
+        assert!(content.contains("alwaysApply: true"));
+    }
+}
@@ -0,0 +1,458 @@
+//! Removing proact-managed content
+//!
+//! Projects generated with a manifest (`.proact/manifest.json`) are cleaned
+//! exactly: files proact created are deleted unless they were edited since,
+//! managed sections are stripped, and learnings and MCP servers proact merged
+//! into existing files are removed again. Without a manifest, managed
+//! sections are stripped from the files proact writes them into and the
+//! Cursor rule is deleted; everything else is left alone.
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::agents::Agent;
+use crate::learnings;
+use crate::managed;
+use crate::manifest::{FileRecord, MANIFEST_PATH, Manifest, Ownership};
+use crate::mcp;
+
+/// What happened (or would happen) to a file during clean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanAction {
+    /// The file only held proact content and was deleted
+    Deleted,
+    /// The managed section was removed; the user's content was kept
+    Stripped,
+    /// Learnings or MCP servers added by proact were removed
+    Pruned,
+    /// Proact created the file but it was edited since, so it was kept
+    Kept,
+}
+
+impl CleanAction {
+    /// Human-readable label used in the run summary
+    pub fn label(self, dry_run: bool) -> &'static str {
+        match (self, dry_run) {
+            (Self::Deleted, false) => "Deleted",
+            (Self::Deleted, true) => "Would delete",
+            (Self::Stripped, false) => "Removed managed section from",
+            (Self::Stripped, true) => "Would remove managed section from",
+            (Self::Pruned, false) => "Removed proact entries from",
+            (Self::Pruned, true) => "Would remove proact entries from",
+            (Self::Kept, _) => "Kept (edited since proact created it)",
+        }
+    }
+}
+
+/// A file changed by clean
+#[derive(Debug, Clone)]
+pub struct Cleaned {
+    pub path: PathBuf,
+    pub action: CleanAction,
+}
+
+/// Remove proact-managed content from the project
+///
+/// Uses the project's manifest when there is one. Otherwise `templates` are
+/// the extra templates rendered into `output_dir`.
+pub fn clean(
+    target_path: &Path,
+    output_dir: &Path,
+    templates: &[PathBuf],
+    verbose: bool,
+    dry_run: bool,
+) -> Result<Vec<Cleaned>> {
+    match Manifest::load(target_path)? {
+        Some(manifest) => clean_recorded(target_path, &manifest, verbose, dry_run),
+        None => clean_known_files(target_path, output_dir, templates, verbose, dry_run),
+    }
+}
+
+/// Remove the content recorded in the manifest, then the manifest itself
+fn clean_recorded(
+    target_path: &Path,
+    manifest: &Manifest,
+    verbose: bool,
+    dry_run: bool,
+) -> Result<Vec<Cleaned>> {
+    if let Some(record) = manifest
+        .files
+        .iter()
+        .find(|record| !is_inside_project(&record.path))
+    {
+        bail!(
+            "{MANIFEST_PATH} records a path outside the project: {}",
+            record.path
+        );
+    }
+
+    let mut cleaned = Vec::new();
+
+    for record in &manifest.files {
+        let Some(ownership) = record.ownership else {
+            continue;
+        };
+        let path = target_path.join(&record.path);
+        if !path.exists() {
+            continue;
+        }
+        let Ok(content) = String::from_utf8(fs::read(&path)?) else {
+            if verbose {
+                eprintln!("# Keeping {}: no longer a text file", path.display());
+            }
+            cleaned.push(Cleaned {
+                path: path.clone(),
+                action: CleanAction::Kept,
+            });
+            continue;
+        };
+        let cleaned_file = match ownership {
+            Ownership::Managed => strip(&path, &content, verbose, dry_run)?,
+            Ownership::Created => {
+                if record.hash == managed::content_hash(&content) {
+                    Some(delete(&path, verbose, dry_run)?)
+                } else {
+                    if verbose {
+                        eprintln!(
+                            "# Keeping {}: edited since proact created it",
+                            path.display()
+                        );
+                    }
+                    Some(Cleaned {
+                        path: path.clone(),
+                        action: CleanAction::Kept,
+                    })
+                }
+            }
+            Ownership::Merged => prune(&path, record, &content, verbose, dry_run)?,
+        };
+        cleaned.extend(cleaned_file);
+    }
+
+    cleaned.push(delete(&target_path.join(MANIFEST_PATH), verbose, dry_run)?);
+
+    if !dry_run {
+        for file in cleaned
+            .iter()
+            .filter(|file| file.action == CleanAction::Deleted)
+        {
+            remove_empty_parents(target_path, &file.path)?;
+        }
+    }
+
+    Ok(cleaned)
+}
+
+/// Whether a manifest path is relative and made only of plain names, so it
+/// cannot reach outside the project
+fn is_inside_project(path: &str) -> bool {
+    let mut components = Path::new(path).components().peekable();
+    components.peek().is_some()
+        && components.all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Strip managed sections from the files proact writes them into
+fn clean_known_files(
+    target_path: &Path,
+    output_dir: &Path,
+    templates: &[PathBuf],
+    verbose: bool,
+    dry_run: bool,
+) -> Result<Vec<Cleaned>> {
+    let mut managed_files: Vec<PathBuf> = ["ai_agent_instructions.md", "process.md", "tools.md"]
+        .iter()
+        .map(|name| output_dir.join(name))
+        .collect();
+    managed_files.extend(
+        templates
+            .iter()
+            .filter_map(|template| template.file_name())
+            .map(|name| output_dir.join(name)),
+    );
+    managed_files.extend(
+        [Agent::Claude, Agent::Agents, Agent::Gemini, Agent::Copilot]
+            .iter()
+            .map(|agent| target_path.join(agent.entry_path())),
+    );
+
+    let mut cleaned = Vec::new();
+
+    for path in managed_files {
+        if !path.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        cleaned.extend(strip(&path, &content, verbose, dry_run)?);
+    }
+
+    // The Cursor rule file is owned by proact entirely
+    let cursor_rule = target_path.join(Agent::Cursor.entry_path());
+    if cursor_rule.exists() {
+        cleaned.push(delete(&cursor_rule, verbose, dry_run)?);
+    }
+
+    Ok(cleaned)
+}
+
+/// Remove the managed section of a file, deleting it if nothing else remains
+fn strip(path: &Path, content: &str, verbose: bool, dry_run: bool) -> Result<Option<Cleaned>> {
+    let Some(remaining) = managed::strip_section(content) else {
+        if verbose {
+            eprintln!("# No managed section in {}", path.display());
+        }
+        return Ok(None);
+    };
+
+    if remaining.trim().is_empty() {
+        return delete(path, verbose, dry_run).map(Some);
+    }
+    if verbose {
+        eprintln!(
+            "strip {} (keeping {} bytes)",
+            path.display(),
+            remaining.len()
+        );
+    }
+    if !dry_run {
+        fs::write(path, remaining)?;
+    }
+    Ok(Some(Cleaned {
+        path: path.to_path_buf(),
+        action: CleanAction::Stripped,
+    }))
+}
+
+/// Remove the learnings or MCP servers proact merged into a file
+fn prune(
+    path: &Path,
+    record: &FileRecord,
+    content: &str,
+    verbose: bool,
+    dry_run: bool,
+) -> Result<Option<Cleaned>> {
+    let pruned = if mcp::is_config_file(&record.path) {
+        mcp::remove_servers(&record.path, content, &record.entries)
+            .with_context(|| format!("Failed to remove MCP servers from {}", path.display()))?
+    } else {
+        learnings::remove(content, &record.entries)
+    };
+    let Some(pruned) = pruned else {
+        return Ok(None);
+    };
+
+    if verbose {
+        eprintln!("prune {} ({})", path.display(), record.entries.join(", "));
+    }
+    if !dry_run {
+        fs::write(path, pruned)?;
+    }
+    Ok(Some(Cleaned {
+        path: path.to_path_buf(),
+        action: CleanAction::Pruned,
+    }))
+}
+
+/// Remove directories left empty by deleting `path`, up to the project root
+fn remove_empty_parents(target_path: &Path, path: &Path) -> Result<()> {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == target_path
+            || !current.starts_with(target_path)
+            || !current.exists()
+            || fs::read_dir(current)?.next().is_some()
+        {
+            break;
+        }
+        fs::remove_dir(current)?;
+        dir = current.parent();
+    }
+    Ok(())
+}
+
+fn delete(path: &Path, verbose: bool, dry_run: bool) -> Result<Cleaned> {
+    if verbose {
+        eprintln!("rm {}", path.display());
+    }
+    if !dry_run {
+        fs::remove_file(path)?;
+    }
+    Ok(Cleaned {
+        path: path.to_path_buf(),
+        action: CleanAction::Deleted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::setup_test_dir;
+    use crate::managed::FileAction;
+
+    #[test]
+    fn test_clean_strips_and_deletes() {
+        let target = setup_test_dir("clean", "strip-and-delete");
+        let docs = target.join("docs");
+        fs::create_dir_all(&docs).unwrap();
+
+        let (generated, _) = managed::merge(None, "generated");
+        fs::write(docs.join("process.md"), &generated).unwrap();
+        let (claude, _) = managed::merge(Some("# Our notes\n"), "generated");
+        fs::write(target.join("CLAUDE.md"), &claude).unwrap();
+        fs::write(docs.join("tools.md"), "# Hand-written\n").unwrap();
+
+        let dry = clean(&target, &docs, &[], false, true).unwrap();
+        assert_eq!(dry.len(), 2);
+        assert!(docs.join("process.md").exists());
+
+        let cleaned = clean(&target, &docs, &[], false, false).unwrap();
+        assert_eq!(cleaned[0].action, CleanAction::Deleted);
+        assert_eq!(cleaned[1].action, CleanAction::Stripped);
+        assert!(!docs.join("process.md").exists());
+        assert_eq!(
+            fs::read_to_string(target.join("CLAUDE.md")).unwrap(),
+            "# Our notes\n"
+        );
+        assert_eq!(
+            fs::read_to_string(docs.join("tools.md")).unwrap(),
+            "# Hand-written\n"
+        );
+    }
+
+    #[test]
+    fn test_clean_uses_manifest() {
+        let target = setup_test_dir("clean", "manifest");
+        let docs = target.join("docs");
+        fs::create_dir_all(&docs).unwrap();
+
+        let (claude, _) = managed::merge(Some("# Our notes\n"), "generated");
+        fs::write(target.join("CLAUDE.md"), &claude).unwrap();
+        fs::write(target.join("LICENSE"), "MIT").unwrap();
+        fs::write(target.join("COPYRIGHT"), "edited").unwrap();
+        fs::write(
+            target.join(".mcp.json"),
+            r#"{"mcpServers": {"github": {}, "playwright": {}}}"#,
+        )
+        .unwrap();
+        fs::write(docs.join("learnings.md"), "### 1. Ours\n\nLocal.\n").unwrap();
+
+        let record = |path: &str, ownership, content: &str, entries: &[&str]| FileRecord {
+            path: path.to_string(),
+            action: FileAction::Created,
+            bytes: content.len(),
+            hash: managed::content_hash(content),
+            ownership: Some(ownership),
+            entries: entries.iter().map(|entry| entry.to_string()).collect(),
+        };
+        let manifest = Manifest {
+            files: vec![
+                record("CLAUDE.md", Ownership::Managed, &claude, &[]),
+                record("LICENSE", Ownership::Created, "MIT", &[]),
+                record("COPYRIGHT", Ownership::Created, "original", &[]),
+                record(".mcp.json", Ownership::Merged, "", &["playwright"]),
+                record("docs/learnings.md", Ownership::Merged, "", &["seed"]),
+            ],
+            ..Manifest::default()
+        };
+        manifest.save(&target, false).unwrap();
+
+        let dry = clean(&target, &docs, &[], false, true).unwrap();
+        assert_eq!(dry.len(), 5);
+        assert!(target.join("LICENSE").exists());
+
+        let cleaned = clean(&target, &docs, &[], false, false).unwrap();
+        let actions: Vec<CleanAction> = cleaned.iter().map(|file| file.action).collect();
+        assert_eq!(
+            actions,
+            [
+                CleanAction::Stripped,
+                CleanAction::Deleted,
+                CleanAction::Kept,
+                CleanAction::Pruned,
+                CleanAction::Deleted,
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("CLAUDE.md")).unwrap(),
+            "# Our notes\n"
+        );
+        assert!(!target.join("LICENSE").exists());
+        assert_eq!(
+            fs::read_to_string(target.join("COPYRIGHT")).unwrap(),
+            "edited"
+        );
+        assert!(
+            !fs::read_to_string(target.join(".mcp.json"))
+                .unwrap()
+                .contains("playwright")
+        );
+        assert!(!target.join(".proact").exists());
+    }
+
+    #[test]
+    fn test_clean_skips_replaced_binary_files() {
+        let target = setup_test_dir("clean", "binary");
+
+        let binary = [0xff, 0xfe, 0x00, 0x80];
+        fs::write(target.join("LICENSE"), binary).unwrap();
+        fs::write(target.join("notes.bin"), binary).unwrap();
+
+        let record = |path: &str, ownership| FileRecord {
+            path: path.to_string(),
+            action: FileAction::Created,
+            bytes: 3,
+            hash: managed::content_hash("MIT"),
+            ownership,
+            entries: Vec::new(),
+        };
+        let manifest = Manifest {
+            files: vec![
+                record("LICENSE", Some(Ownership::Created)),
+                record("notes.bin", None),
+            ],
+            ..Manifest::default()
+        };
+        manifest.save(&target, false).unwrap();
+
+        let cleaned = clean(&target, &target, &[], false, false).unwrap();
+        assert_eq!(cleaned[0].action, CleanAction::Kept);
+        assert_eq!(cleaned.len(), 2);
+        assert_eq!(fs::read(target.join("LICENSE")).unwrap(), binary);
+        assert_eq!(fs::read(target.join("notes.bin")).unwrap(), binary);
+        assert!(!target.join(".proact").exists());
+    }
+
+    #[test]
+    fn test_clean_rejects_paths_outside_project() {
+        let target = setup_test_dir("clean", "tampered");
+        let outside = target.with_file_name("tampered-outside.md");
+        fs::write(&outside, "MIT").unwrap();
+
+        for path in [
+            "../tampered-outside.md",
+            "docs/../../tampered-outside.md",
+            &fs::canonicalize(&outside).unwrap().display().to_string(),
+            "",
+        ] {
+            let manifest = Manifest {
+                files: vec![FileRecord {
+                    path: path.to_string(),
+                    action: FileAction::Created,
+                    bytes: 3,
+                    hash: managed::content_hash("MIT"),
+                    ownership: Some(Ownership::Created),
+                    entries: Vec::new(),
+                }],
+                ..Manifest::default()
+            };
+            manifest.save(&target, false).unwrap();
+
+            let error = clean(&target, &target, &[], false, false).unwrap_err();
+            assert!(error.to_string().contains("outside the project"), "{path}");
+            assert!(outside.exists());
+            assert!(target.join(MANIFEST_PATH).exists());
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use proact::report::OutputFormat;
+use proact::{Agent, DocSection, Overrides};
+
 const LONG_ABOUT: &str = "\
 Proact generates comprehensive documentation that instructs AI coding agents \
 to follow best practices, apply continuous improvement, and use tools like \
@@ -10,7 +13,7 @@ AI CODING AGENT NOTES:
 When working with projects generated by Proact, you should:
   1. Read the generated docs/ai_agent_instructions.md for project guidelines
   2. Follow the checkpoint process described in the documentation
-  3. Update docs/learnings.md when encountering issues
+  3. Record issues in docs/learnings.md with `proact learn`
   4. Run proact again if project structure changes significantly
 
 For CLI projects generated with this pattern:
@@ -23,21 +26,53 @@ For CLI projects generated with this pattern:
 ///
 /// This module handles command-line argument parsing for the Proact tool,
 /// which generates comprehensive documentation for AI coding agents.
+///
+/// Running `proact [OPTIONS] <TARGET>` without a subcommand is an alias for
+/// `proact init`.
 #[derive(Parser, Debug)]
 #[command(
     name = "proact",
     about = "Generate documentation for AI coding agents",
     long_about = LONG_ABOUT,
     version,
-    author
+    author,
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true
 )]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub generate: GenerateArgs,
+
+    /// Output format of the run report
+    ///
+    /// `json` prints a single structured report on stdout instead of the
+    /// text summary; progress and verbose output still go to stderr.
+    #[arg(
+        long = "format",
+        value_enum,
+        global = true,
+        default_value_t = OutputFormat::Text,
+        help = "Report format (text, json)"
+    )]
+    pub format: OutputFormat,
+}
+
+/// Options shared by `init`, `update`, `check` and the bare invocation
+#[derive(clap::Args, Debug)]
+pub struct GenerateArgs {
     /// Path to the target project directory
     ///
     /// This should be an existing directory where the AI agent documentation
     /// will be tailored for. The path can be absolute or relative.
-    #[arg(value_name = "TARGET", help = "Path to an existing project directory")]
-    pub target: PathBuf,
+    #[arg(
+        value_name = "TARGET",
+        required = true,
+        help = "Path to an existing project directory"
+    )]
+    pub target: Option<PathBuf>,
 
     /// Enable verbose output
     ///
@@ -49,15 +84,15 @@ pub struct Args {
     /// Output directory for generated documentation
     ///
     /// The directory where the AI agent documentation will be written.
-    /// Will be created if it doesn't exist.
+    /// Will be created if it doesn't exist. Defaults to `output-dir` from
+    /// .proact.toml, or `docs`.
     #[arg(
         short = 'o',
         long = "output-dir",
         value_name = "DIR",
-        default_value = "docs",
-        help = "Output directory for generated documentation"
+        help = "Output directory for generated documentation [default: docs]"
     )]
-    pub output_dir: PathBuf,
+    pub output_dir: Option<PathBuf>,
 
     /// Perform a dry run without creating files
     ///
@@ -69,36 +104,267 @@ pub struct Args {
         help = "Show what would be done without actually doing it"
     )]
     pub dry_run: bool,
+
+    /// Also generate agent-specific entry files
+    ///
+    /// Writes (or updates a managed section in) the file each agent looks
+    /// for: CLAUDE.md, AGENTS.md, GEMINI.md, .github/copilot-instructions.md
+    /// and .cursor/rules/proact.mdc. Accepts a comma-separated list; without
+    /// a value all agents are generated.
+    #[arg(
+        long = "agents",
+        value_name = "AGENTS",
+        value_enum,
+        value_delimiter = ',',
+        num_args = 0..=1,
+        default_missing_value = "all",
+        help = "Generate agent entry files (claude,agents,gemini,copilot,cursor,all)"
+    )]
+    pub agents: Vec<Agent>,
+
+    /// Also write agent instructions into each workspace package
+    ///
+    /// For Cargo, pnpm/npm/yarn and Python workspaces, writes (or updates a
+    /// managed section in) AGENTS.md in every member package, or the
+    /// CLAUDE.md/GEMINI.md files of the agents selected with --agents, with
+    /// the package's own commands and guidance.
+    #[arg(
+        long = "per-package",
+        help = "Write agent instructions into each workspace package"
+    )]
+    pub per_package: bool,
+
+    /// Write MCP server configuration files
+    ///
+    /// Adds the Playwright MCP server (and any servers declared with
+    /// --mcp-server) to .mcp.json, opencode.json and .gemini/settings.json,
+    /// merging with existing configuration without removing other servers.
+    #[arg(
+        long = "mcp",
+        help = "Write MCP server config (.mcp.json, opencode.json, .gemini/settings.json)"
+    )]
+    pub mcp: bool,
+
+    /// Declare an additional MCP server
+    ///
+    /// Format: NAME=COMMAND [ARGS...], e.g. "context7=npx -y @upstash/context7-mcp".
+    /// May be given multiple times. Implies --mcp.
+    #[arg(
+        long = "mcp-server",
+        value_name = "NAME=COMMAND",
+        help = "Declare an additional MCP server (implies --mcp)"
+    )]
+    pub mcp_servers: Vec<String>,
+
+    /// Print a unified diff of every file that would change
+    ///
+    /// Shows exactly what a regeneration would create, overwrite or append
+    /// to without writing anything. Implies dry-run mode.
+    #[arg(
+        long = "diff",
+        help = "Show a unified diff of changes without writing files"
+    )]
+    pub diff: bool,
+
+    /// Check that generated documentation is up to date
+    ///
+    /// Regenerates everything in memory and compares it with the files on
+    /// disk without writing anything. Exits with a non-zero status and a
+    /// per-file summary when any file is missing or stale, so CI can enforce
+    /// that agent docs stay in sync with the pinned proact version.
+    #[arg(
+        long = "check",
+        conflicts_with_all = ["dry_run", "diff"],
+        help = "Fail if generated files are missing or out of date"
+    )]
+    pub check: bool,
+
+    /// Overwrite existing LICENSE and COPYRIGHT files
+    ///
+    /// By default an existing legal file that differs from the generated
+    /// content is kept and reported as skipped.
+    #[arg(
+        long = "force",
+        conflicts_with = "backup",
+        help = "Overwrite existing LICENSE/COPYRIGHT files that differ"
+    )]
+    pub force: bool,
+
+    /// Back up existing LICENSE and COPYRIGHT files before replacing them
+    ///
+    /// The existing file is copied to FILE.bak (or FILE.bak.N if that
+    /// exists) and then replaced with the generated content.
+    #[arg(
+        long = "backup",
+        help = "Save differing LICENSE/COPYRIGHT files to .bak before replacing them"
+    )]
+    pub backup: bool,
+}
+
+impl GenerateArgs {
+    /// The settings these flags override in the configuration
+    pub fn overrides(&self) -> Overrides {
+        Overrides {
+            output_dir: self.output_dir.clone(),
+            agents: self.agents.clone(),
+            mcp: self.mcp,
+            mcp_servers: self.mcp_servers.clone(),
+            force: self.force,
+            backup: self.backup,
+            per_package: self.per_package,
+        }
+    }
+}
+
+/// Subcommands
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate documentation for a project (default without a subcommand)
+    Init(GenerateArgs),
+    /// Refresh proact-managed content in files that already exist
+    Update(GenerateArgs),
+    /// Fail if generated files are missing or out of date
+    Check(GenerateArgs),
+    /// Print a section of the agent instructions to stdout
+    Show(ShowArgs),
+    /// Remove proact-managed content from a project
+    Clean(CleanArgs),
+    /// Record a new learning in learnings.md
+    Learn(LearnArgs),
+    /// Merge learnings from projects into the shared learnings library
+    Harvest(HarvestArgs),
+}
+
+/// Arguments for `proact show`
+#[derive(clap::Args, Debug)]
+pub struct ShowArgs {
+    /// Section to print
+    #[arg(value_enum)]
+    pub section: DocSection,
+
+    /// Project directory used for project-specific notes
+    #[arg(value_name = "TARGET", default_value = ".")]
+    pub target: PathBuf,
+}
+
+/// Arguments for `proact clean`
+#[derive(clap::Args, Debug)]
+pub struct CleanArgs {
+    /// Project directory to clean
+    #[arg(value_name = "TARGET")]
+    pub target: PathBuf,
+
+    /// Output directory of the generated documentation [default: docs]
+    #[arg(short = 'o', long = "output-dir", value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
+
+    /// Show what would be removed without changing anything
+    #[arg(short = 'n', long = "dry-run")]
+    pub dry_run: bool,
+}
+
+/// Arguments for `proact learn`
+///
+/// The entry follows the Issue / Resolution / Proactive Prevention structure
+/// of learnings.md. Agents can pass the same fields as a JSON object on
+/// stdin with --json.
+#[derive(clap::Args, Debug)]
+pub struct LearnArgs {
+    /// Project directory containing the learnings
+    #[arg(value_name = "TARGET", default_value = ".")]
+    pub target: PathBuf,
+
+    /// Short title of the learning (used to detect duplicates)
+    #[arg(long, required_unless_present = "json")]
+    pub title: Option<String>,
+
+    /// What went wrong
+    #[arg(long, required_unless_present = "json")]
+    pub issue: Option<String>,
+
+    /// How it was fixed
+    #[arg(long, required_unless_present = "json")]
+    pub resolution: Option<String>,
+
+    /// How to avoid it in the future (repeat for several bullet points)
+    #[arg(long)]
+    pub prevention: Vec<String>,
+
+    /// Read the learning as a JSON object from stdin
+    ///
+    /// Format: {"title": "...", "issue": "...", "resolution": "...",
+    /// "prevention": ["...", "..."]}
+    #[arg(long, conflicts_with_all = ["title", "issue", "resolution", "prevention"])]
+    pub json: bool,
+
+    /// Output directory containing learnings.md [default: docs]
+    #[arg(short = 'o', long = "output-dir", value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
+
+    /// Show the entry that would be added without writing it
+    #[arg(short = 'n', long = "dry-run")]
+    pub dry_run: bool,
+}
+
+/// Arguments for `proact harvest`
+#[derive(clap::Args, Debug)]
+pub struct HarvestArgs {
+    /// Project directories to collect learnings from
+    #[arg(value_name = "DIRS", required = true)]
+    pub dirs: Vec<PathBuf>,
+
+    /// Learnings library to merge into [default: learnings-library from config]
+    #[arg(long, value_name = "FILE")]
+    pub library: Option<PathBuf>,
+
+    /// Write the merged library here instead of updating it in place
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Print a unified diff of the library for review without writing it
+    #[arg(long)]
+    pub diff: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Parse a bare invocation and return its generate options
+    fn parse(args: &[&str]) -> GenerateArgs {
+        Args::parse_from(args).generate
+    }
+
     #[test]
     fn test_cli_parsing() {
-        let args = Args::parse_from(["proact", "../test-proj"]);
-        assert_eq!(args.target, PathBuf::from("../test-proj"));
-        assert_eq!(args.output_dir, PathBuf::from("docs"));
+        let args = parse(&["proact", "../test-proj"]);
+        assert_eq!(args.target, Some(PathBuf::from("../test-proj")));
+        assert_eq!(args.output_dir, None);
         assert!(!args.verbose);
         assert!(!args.dry_run);
+        assert!(!args.check);
+        assert!(!args.diff);
+        assert!(args.agents.is_empty());
+        assert!(!args.per_package);
+        assert!(!args.mcp);
+        assert!(args.mcp_servers.is_empty());
     }
 
     #[test]
     fn test_cli_with_verbose() {
-        let args = Args::parse_from(["proact", "-v", "../test-proj"]);
+        let args = parse(&["proact", "-v", "../test-proj"]);
         assert!(args.verbose);
     }
 
     #[test]
     fn test_cli_with_output_dir() {
-        let args = Args::parse_from(["proact", "-o", "./output", "../test-proj"]);
-        assert_eq!(args.output_dir, PathBuf::from("./output"));
+        let args = parse(&["proact", "-o", "./output", "../test-proj"]);
+        assert_eq!(args.output_dir, Some(PathBuf::from("./output")));
     }
 
     #[test]
     fn test_cli_long_options() {
-        let args = Args::parse_from([
+        let args = parse(&[
             "proact",
             "--verbose",
             "--output-dir",
@@ -106,19 +372,147 @@ mod tests {
             "../project",
         ]);
         assert!(args.verbose);
-        assert_eq!(args.output_dir, PathBuf::from("./custom"));
-        assert_eq!(args.target, PathBuf::from("../project"));
+        assert_eq!(args.output_dir, Some(PathBuf::from("./custom")));
+        assert_eq!(args.target, Some(PathBuf::from("../project")));
     }
 
     #[test]
     fn test_cli_with_dry_run() {
-        let args = Args::parse_from(["proact", "-n", "../test-proj"]);
+        let args = parse(&["proact", "-n", "../test-proj"]);
         assert!(args.dry_run);
     }
 
     #[test]
     fn test_cli_with_dry_run_long() {
-        let args = Args::parse_from(["proact", "--dry-run", "../test-proj"]);
+        let args = parse(&["proact", "--dry-run", "../test-proj"]);
         assert!(args.dry_run);
     }
+
+    #[test]
+    fn test_cli_with_agents_list() {
+        let args = parse(&["proact", "--agents", "claude,cursor", "../test-proj"]);
+        assert_eq!(args.agents, vec![Agent::Claude, Agent::Cursor]);
+    }
+
+    #[test]
+    fn test_cli_with_agents_without_value() {
+        let args = parse(&["proact", "../test-proj", "--agents"]);
+        assert_eq!(args.agents, vec![Agent::All]);
+    }
+
+    #[test]
+    fn test_cli_with_mcp_servers() {
+        let args = parse(&[
+            "proact",
+            "--mcp-server",
+            "a=cmd-a",
+            "--mcp-server",
+            "b=cmd-b --flag",
+            "../test-proj",
+        ]);
+        assert_eq!(args.mcp_servers, vec!["a=cmd-a", "b=cmd-b --flag"]);
+    }
+
+    #[test]
+    fn test_cli_with_diff() {
+        let args = parse(&["proact", "--diff", "../test-proj"]);
+        assert!(args.diff);
+    }
+
+    #[test]
+    fn test_cli_with_check() {
+        let args = parse(&["proact", "--check", "../test-proj"]);
+        assert!(args.check);
+        assert!(Args::try_parse_from(["proact", "--check", "-n", "../test-proj"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_force_conflicts_with_backup() {
+        let args = parse(&["proact", "--backup", "../test-proj"]);
+        assert!(args.backup);
+        assert!(!args.force);
+        assert!(Args::try_parse_from(["proact", "--force", "--backup", "../test-proj"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_requires_target_without_subcommand() {
+        assert!(Args::try_parse_from(["proact"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_learn_subcommand() {
+        let args = Args::parse_from([
+            "proact",
+            "learn",
+            "--title",
+            "Flaky tests",
+            "--issue",
+            "Tests failed randomly",
+            "--resolution",
+            "Wait for network idle",
+            "--prevention",
+            "Never sleep in tests",
+            "--prevention",
+            "Use explicit waits",
+        ]);
+        let Some(Command::Learn(learn)) = args.command else {
+            panic!("expected learn subcommand");
+        };
+        assert_eq!(learn.target, PathBuf::from("."));
+        assert_eq!(learn.title.as_deref(), Some("Flaky tests"));
+        assert_eq!(learn.prevention.len(), 2);
+    }
+
+    #[test]
+    fn test_cli_subcommands_share_generate_options() {
+        let args = Args::parse_from(["proact", "update", "-o", "documentation", "proj"]);
+        let Some(Command::Update(update)) = args.command else {
+            panic!("expected update subcommand");
+        };
+        assert_eq!(update.target, Some(PathBuf::from("proj")));
+        assert_eq!(update.output_dir, Some(PathBuf::from("documentation")));
+
+        let args = Args::parse_from(["proact", "show", "quality"]);
+        let Some(Command::Show(show)) = args.command else {
+            panic!("expected show subcommand");
+        };
+        assert_eq!(show.section, DocSection::Quality);
+        assert_eq!(show.target, PathBuf::from("."));
+
+        assert!(Args::try_parse_from(["proact", "show", "nonsense"]).is_err());
+        assert!(Args::try_parse_from(["proact", "clean", "proj", "-n"]).is_ok());
+    }
+
+    #[test]
+    fn test_cli_harvest_subcommand() {
+        let args = Args::parse_from(["proact", "harvest", "a", "b", "--library", "lib.md"]);
+        let Some(Command::Harvest(harvest)) = args.command else {
+            panic!("expected harvest subcommand");
+        };
+        assert_eq!(harvest.dirs, vec![PathBuf::from("a"), PathBuf::from("b")]);
+        assert_eq!(harvest.library, Some(PathBuf::from("lib.md")));
+        assert!(Args::try_parse_from(["proact", "harvest"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_format() {
+        assert_eq!(
+            Args::parse_from(["proact", "proj"]).format,
+            OutputFormat::Text
+        );
+        assert_eq!(
+            Args::parse_from(["proact", "--format", "json", "proj"]).format,
+            OutputFormat::Json
+        );
+        let args = Args::parse_from(["proact", "clean", "proj", "--format", "json"]);
+        assert_eq!(args.format, OutputFormat::Json);
+        assert!(Args::try_parse_from(["proact", "--format", "yaml", "proj"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_learn_requires_fields_or_json() {
+        assert!(Args::try_parse_from(["proact", "learn", "--title", "x"]).is_err());
+        assert!(Args::try_parse_from(["proact", "learn", "--json"]).is_ok());
+        assert!(Args::try_parse_from(["proact", "learn", "--json", "--title", "x"]).is_err());
+    }
 }
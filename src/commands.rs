@@ -0,0 +1,531 @@
+//! Commands inferred from the target project
+//!
+//! Ecosystems come with standard build/test/lint/format commands. The
+//! project's own entry points replace them wherever they exist:
+//!
+//! - package.json scripts, run with the package manager that owns the
+//!   lockfile (npm, yarn, pnpm or bun)
+//! - tox environments, nox sessions and hatch scripts
+//! - `.cargo/config.toml` aliases
+//! - the Maven wrapper (`./mvnw`) in place of a system-wide `mvn`
+//! - Makefile and justfile targets, which win over everything else
+//!
+//! A role (test, lint, ...) is filled from the first target whose name is
+//! one of the role's conventional names.
+
+use std::fs;
+use std::path::Path;
+use toml::Value;
+
+use crate::detector::Commands;
+use crate::files::{read_json, read_toml};
+
+/// Conventional target names for each role, most specific first
+const BUILD: &[&str] = &["build"];
+const TEST: &[&str] = &["test", "tests"];
+const LINT: &[&str] = &["lint", "clippy"];
+const FORMAT: &[&str] = &["format", "fmt"];
+const FORMAT_CHECK: &[&str] = &[
+    "format:check",
+    "format-check",
+    "check-format",
+    "fmt:check",
+    "fmt-check",
+    "check-fmt",
+];
+
+/// The commands the project actually uses, starting from the standard
+/// `commands` of the named ecosystem (`rust`, `maven`, ...)
+pub fn infer(target_path: &Path, ecosystem: &str, mut commands: Commands) -> Commands {
+    match ecosystem {
+        "javascript" => infer_npm(target_path, &mut commands),
+        "python" => infer_python(target_path, &mut commands),
+        "rust" => {
+            let aliases = cargo_aliases(target_path);
+            apply(&mut commands, &aliases, |alias| format!("cargo {alias}"));
+        }
+        "maven" if target_path.join("mvnw").is_file() => use_maven_wrapper(&mut commands),
+        _ => {}
+    }
+
+    apply(&mut commands, &make_targets(target_path), |target| {
+        format!("make {target}")
+    });
+    apply(&mut commands, &just_recipes(target_path), |recipe| {
+        format!("just {recipe}")
+    });
+    commands
+}
+
+/// The JavaScript package manager, from package.json's `packageManager`
+/// field or the lockfile; npm when neither says otherwise
+///
+/// A workspace package without either inherits them from the enclosing
+/// workspace root (a directory with pnpm-workspace.yaml or package.json
+/// `workspaces`).
+pub fn package_manager(target_path: &Path) -> &'static str {
+    let workspace_roots = target_path.ancestors().skip(1).filter(|dir| {
+        dir.join("pnpm-workspace.yaml").is_file()
+            || read_json(&dir.join("package.json"))
+                .is_some_and(|manifest| manifest.get("workspaces").is_some())
+    });
+    std::iter::once(target_path)
+        .chain(workspace_roots)
+        .find_map(declared_package_manager)
+        .unwrap_or("npm")
+}
+
+/// The package manager named by package.json or a lockfile in `dir`
+fn declared_package_manager(dir: &Path) -> Option<&'static str> {
+    const MANAGERS: [(&str, &[&str]); 4] = [
+        ("pnpm", &["pnpm-lock.yaml"]),
+        ("yarn", &["yarn.lock"]),
+        ("bun", &["bun.lock", "bun.lockb"]),
+        ("npm", &["package-lock.json", "npm-shrinkwrap.json"]),
+    ];
+
+    let declared = read_json(&dir.join("package.json")).and_then(|manifest| {
+        manifest
+            .get("packageManager")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+    });
+    if let Some(declared) = declared
+        && let Some((manager, _)) = MANAGERS
+            .iter()
+            .find(|(manager, _)| declared.starts_with(&format!("{manager}@")))
+    {
+        return Some(manager);
+    }
+
+    MANAGERS
+        .iter()
+        .find(|(_, lockfiles)| lockfiles.iter().any(|lockfile| dir.join(lockfile).exists()))
+        .map(|(manager, _)| *manager)
+}
+
+/// The command that updates the project's dependencies within their
+/// declared ranges
+pub fn update_command(target_path: &Path) -> &'static str {
+    match package_manager(target_path) {
+        "yarn" if is_yarn_berry(target_path) => "yarn up",
+        "yarn" => "yarn upgrade",
+        "pnpm" => "pnpm update",
+        "bun" => "bun update",
+        _ => "npm update",
+    }
+}
+
+/// Whether the project uses Yarn 2 or later, which replaced `yarn upgrade`
+/// with `yarn up`: it declares such a version in `packageManager` or has a
+/// `.yarnrc.yml` (Yarn 1 reads `.yarnrc`)
+fn is_yarn_berry(target_path: &Path) -> bool {
+    target_path.ancestors().any(|dir| {
+        dir.join(".yarnrc.yml").is_file()
+            || read_json(&dir.join("package.json"))
+                .and_then(|manifest| {
+                    manifest
+                        .get("packageManager")
+                        .and_then(serde_json::Value::as_str)
+                        .and_then(|declared| declared.strip_prefix("yarn@"))
+                        .map(|version| !version.starts_with("1."))
+                })
+                .unwrap_or(false)
+    })
+}
+
+/// package.json scripts, run with the project's package manager
+///
+/// The npm-based defaults only apply when the project provides them: a
+/// `npm run` default needs the script, and an `npx` tool needs to be a
+/// dependency. Roles without either are left unset.
+fn infer_npm(target_path: &Path, commands: &mut Commands) {
+    let manager = package_manager(target_path);
+    let run = |script: &str| match (manager, script) {
+        // `bun test` runs bun's own test runner instead of the script
+        ("bun", _) => format!("bun run {script}"),
+        (_, "test") => format!("{manager} test"),
+        _ => format!("{manager} run {script}"),
+    };
+    let exec = match manager {
+        "pnpm" => "pnpm exec",
+        "yarn" => "yarn",
+        "bun" => "bunx",
+        _ => "npx",
+    };
+
+    let manifest = read_json(&target_path.join("package.json"));
+    let keys = |field: &str| -> Vec<String> {
+        manifest
+            .as_ref()
+            .and_then(|manifest| manifest.get(field))
+            .and_then(serde_json::Value::as_object)
+            .map(|entries| entries.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+    let mut dependencies = keys("dependencies");
+    dependencies.extend(keys("devDependencies"));
+
+    for command in [
+        &mut commands.build,
+        &mut commands.test,
+        &mut commands.lint,
+        &mut commands.format,
+        &mut commands.format_check,
+    ] {
+        *command = match command.take() {
+            // Filled from the scripts below if the project defines them
+            Some(default) if default.starts_with("npm run ") || default == "npm test" => None,
+            Some(default) if default.starts_with("npx ") => {
+                let rest = &default["npx ".len()..];
+                let tool = rest.split_whitespace().next().unwrap_or_default();
+                dependencies
+                    .iter()
+                    .any(|dependency| dependency == tool)
+                    .then(|| format!("{exec} {rest}"))
+            }
+            other => other,
+        };
+    }
+
+    apply(commands, &keys("scripts"), run);
+}
+
+/// Run the Maven goals through the project's `./mvnw` wrapper
+fn use_maven_wrapper(commands: &mut Commands) {
+    for command in [
+        &mut commands.build,
+        &mut commands.test,
+        &mut commands.lint,
+        &mut commands.format,
+        &mut commands.format_check,
+    ] {
+        if let Some(goals) = command.as_deref().and_then(|cmd| cmd.strip_prefix("mvn ")) {
+            *command = Some(format!("./mvnw {goals}"));
+        }
+    }
+}
+
+/// tox environments, nox sessions and hatch scripts
+fn infer_python(target_path: &Path, commands: &mut Commands) {
+    let pyproject = read_toml(&target_path.join("pyproject.toml"));
+    let tool = |name: &str| {
+        pyproject
+            .as_ref()
+            .and_then(|manifest| manifest.get("tool"))
+            .and_then(|tool| tool.get(name))
+            .and_then(Value::as_table)
+            .cloned()
+    };
+
+    // tox: `tox` runs the test environments; named environments for the rest
+    let tox_ini = fs::read_to_string(target_path.join("tox.ini")).ok();
+    let tox_toml = read_toml(&target_path.join("tox.toml")).or_else(|| tool("tox"));
+    if tox_ini.is_some() || tox_toml.is_some() {
+        let mut environments: Vec<String> = tox_ini
+            .iter()
+            .flat_map(|ini| ini_sections(ini, "testenv:"))
+            .collect();
+        environments.extend(
+            tox_toml
+                .as_ref()
+                .and_then(|tox| tox.get("env"))
+                .and_then(Value::as_table)
+                .into_iter()
+                .flat_map(|envs| envs.keys().cloned()),
+        );
+        commands.test = Some("tox".to_string());
+        apply(commands, &environments, |env| format!("tox -e {env}"));
+    }
+
+    if let Ok(noxfile) = fs::read_to_string(target_path.join("noxfile.py")) {
+        apply(commands, &nox_sessions(&noxfile), |session| {
+            format!("nox -s {session}")
+        });
+    }
+
+    let hatch = read_toml(&target_path.join("hatch.toml")).or_else(|| tool("hatch"));
+    let scripts: Vec<String> = hatch
+        .as_ref()
+        .and_then(|hatch| hatch.get("envs"))
+        .and_then(|envs| envs.get("default"))
+        .and_then(|default| default.get("scripts"))
+        .and_then(Value::as_table)
+        .map(|scripts| scripts.keys().cloned().collect())
+        .unwrap_or_default();
+    apply(commands, &scripts, |script| format!("hatch run {script}"));
+}
+
+/// Fill each role from the first available target with one of its names
+fn apply(commands: &mut Commands, available: &[String], command: impl Fn(&str) -> String) {
+    let roles: [(&mut Option<String>, &[&str]); 5] = [
+        (&mut commands.build, BUILD),
+        (&mut commands.test, TEST),
+        (&mut commands.lint, LINT),
+        (&mut commands.format, FORMAT),
+        (&mut commands.format_check, FORMAT_CHECK),
+    ];
+    for (slot, names) in roles {
+        if let Some(name) = names
+            .iter()
+            .find(|name| available.iter().any(|target| target == *name))
+        {
+            *slot = Some(command(name));
+        }
+    }
+}
+
+/// Aliases declared in `.cargo/config.toml` (or the legacy `.cargo/config`)
+fn cargo_aliases(target_path: &Path) -> Vec<String> {
+    let cargo_dir = target_path.join(".cargo");
+    ["config.toml", "config"]
+        .into_iter()
+        .find_map(|name| read_toml(&cargo_dir.join(name)))
+        .and_then(|config| config.get("alias").and_then(Value::as_table).cloned())
+        .map(|aliases| aliases.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Targets defined in the project's Makefile
+fn make_targets(target_path: &Path) -> Vec<String> {
+    let Some(makefile) = ["GNUmakefile", "makefile", "Makefile"]
+        .into_iter()
+        .find_map(|name| fs::read_to_string(target_path.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+
+    makefile
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#', '.']))
+        .filter_map(|line| {
+            let (targets, rest) = line.split_once(':')?;
+            // `NAME := value` and `NAME ::= value` are assignments
+            (!targets.contains('=') && !rest.starts_with('=') && !rest.starts_with(":="))
+                .then_some(targets)
+        })
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Recipes defined in the project's justfile
+fn just_recipes(target_path: &Path) -> Vec<String> {
+    let Some(justfile) = ["justfile", "Justfile", ".justfile"]
+        .into_iter()
+        .find_map(|name| fs::read_to_string(target_path.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+
+    justfile
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#', '[']))
+        .filter_map(|line| {
+            let line = line.strip_prefix('@').unwrap_or(line);
+            let name_end = line
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(line.len());
+            let (name, rest) = line.split_at(name_end);
+            // Parameters may precede the colon; `:=` is an assignment
+            let (_, after) = rest.split_once(':')?;
+            (!name.is_empty() && !after.starts_with('=')).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Names of the sessions in a noxfile (functions decorated with
+/// `@nox.session`)
+fn nox_sessions(noxfile: &str) -> Vec<String> {
+    let mut sessions = Vec::new();
+    let mut decorated = false;
+    for line in noxfile.lines().map(str::trim_start) {
+        if line.starts_with("@nox.session") {
+            decorated = true;
+        } else if decorated && let Some(rest) = line.strip_prefix("def ") {
+            let name: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect();
+            sessions.push(name);
+            decorated = false;
+        }
+    }
+    sessions
+}
+
+/// Names of the INI sections starting with `prefix`, without the prefix
+fn ini_sections(ini: &str, prefix: &str) -> Vec<String> {
+    ini.lines()
+        .filter_map(|line| line.trim().strip_prefix('[')?.strip_suffix(']'))
+        .filter_map(|section| section.strip_prefix(prefix))
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ProjectDetector;
+    use crate::ecosystem;
+    use crate::files::setup_test_dir;
+
+    fn defaults(ecosystem: &ecosystem::Ecosystem) -> Commands {
+        Commands {
+            build: Some(ecosystem.build.to_string()),
+            test: Some(ecosystem.test.to_string()),
+            lint: Some(ecosystem.lint.to_string()),
+            format: Some(ecosystem.format.to_string()),
+            format_check: Some(ecosystem.format_check.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_npm_scripts_use_lockfile_package_manager() {
+        let dir = setup_test_dir("commands", "pnpm");
+        fs::write(
+            dir.join("package.json"),
+            r#"{
+                "scripts": {"build": "vite build", "test": "vitest", "format:check": "prettier -c ."},
+                "devDependencies": {"prettier": "^3.0.0"}
+            }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("pnpm-lock.yaml"), "").unwrap();
+
+        let commands = infer(&dir, "javascript", defaults(&ecosystem::JAVASCRIPT));
+        assert_eq!(commands.build.as_deref(), Some("pnpm run build"));
+        assert_eq!(commands.test.as_deref(), Some("pnpm test"));
+        // No lint script: no lint command
+        assert_eq!(commands.lint, None);
+        assert_eq!(
+            commands.format.as_deref(),
+            Some("pnpm exec prettier --write .")
+        );
+        assert_eq!(
+            commands.format_check.as_deref(),
+            Some("pnpm run format:check")
+        );
+    }
+
+    #[test]
+    fn test_npm_defaults_need_scripts_and_tools() {
+        let dir = setup_test_dir("commands", "npm-defaults");
+        fs::write(dir.join("package.json"), r#"{"name": "bare"}"#).unwrap();
+
+        let commands = infer(&dir, "javascript", defaults(&ecosystem::JAVASCRIPT));
+        assert_eq!(commands.build, None);
+        assert_eq!(commands.test, None);
+        assert_eq!(commands.lint, None);
+        // prettier is not a dependency
+        assert_eq!(commands.format, None);
+        assert_eq!(commands.format_check, None);
+    }
+
+    #[test]
+    fn test_package_manager() {
+        let dir = setup_test_dir("commands", "package-manager");
+        fs::write(dir.join("package.json"), "{}").unwrap();
+        assert_eq!(package_manager(&dir), "npm");
+
+        fs::write(dir.join("bun.lockb"), "").unwrap();
+        assert_eq!(package_manager(&dir), "bun");
+
+        fs::write(
+            dir.join("package.json"),
+            r#"{"packageManager": "yarn@4.1.0"}"#,
+        )
+        .unwrap();
+        assert_eq!(package_manager(&dir), "yarn");
+        assert_eq!(update_command(&dir), "yarn up");
+
+        fs::write(
+            dir.join("package.json"),
+            r#"{"packageManager": "yarn@1.22.22"}"#,
+        )
+        .unwrap();
+        assert_eq!(update_command(&dir), "yarn upgrade");
+    }
+
+    #[test]
+    fn test_task_runners_win() {
+        let dir = setup_test_dir("commands", "make-and-just");
+        fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+        fs::create_dir_all(dir.join(".cargo")).unwrap();
+        fs::write(
+            dir.join(".cargo").join("config.toml"),
+            "[alias]\nlint = \"clippy --all-targets -- -D warnings\"\nxtask = \"run -p xtask --\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("Makefile"),
+            "CARGO := cargo\n.PHONY: build test\nbuild test: deps\n\tcargo $@\nfmt-check:\n\tcargo fmt --check\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("justfile"),
+            "set shell := [\"bash\", \"-c\"]\nalias t := test\n\n# Run the tests\ntest filter=\"\":\n    cargo test {{filter}}\n",
+        )
+        .unwrap();
+
+        let commands = infer(&dir, "rust", defaults(&ecosystem::RUST));
+        assert_eq!(commands.build.as_deref(), Some("make build"));
+        assert_eq!(commands.test.as_deref(), Some("just test"));
+        assert_eq!(commands.lint.as_deref(), Some("cargo lint"));
+        assert_eq!(commands.format.as_deref(), Some("cargo fmt --all"));
+        assert_eq!(commands.format_check.as_deref(), Some("make fmt-check"));
+    }
+
+    #[test]
+    fn test_maven_wrapper() {
+        let dir = setup_test_dir("commands", "maven-wrapper");
+        fs::write(dir.join("pom.xml"), "<project/>").unwrap();
+        let detected = |dir: &Path| {
+            ecosystem::MAVEN
+                .detect(dir)
+                .and_then(|detection| detection.facts.commands)
+                .unwrap()
+        };
+
+        assert_eq!(detected(&dir).build.as_deref(), Some("mvn package"));
+
+        fs::write(dir.join("mvnw"), "#!/bin/sh\n").unwrap();
+        let commands = detected(&dir);
+        assert_eq!(commands.build.as_deref(), Some("./mvnw package"));
+        assert_eq!(commands.test.as_deref(), Some("./mvnw test"));
+        assert_eq!(
+            commands.format_check.as_deref(),
+            Some("./mvnw spotless:check")
+        );
+    }
+
+    #[test]
+    fn test_python_runners() {
+        let dir = setup_test_dir("commands", "python");
+        fs::write(
+            dir.join("tox.ini"),
+            "[tox]\nenv_list = py312\n\n[testenv]\ncommands = pytest\n\n[testenv:lint]\ncommands = ruff check\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("noxfile.py"),
+            "import nox\n\n@nox.session(python=[\"3.12\"])\ndef format(session):\n    session.run(\"ruff\", \"format\")\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("pyproject.toml"),
+            "[tool.hatch.envs.default.scripts]\nbuild = \"python -m build\"\n",
+        )
+        .unwrap();
+
+        let commands = infer(&dir, "python", defaults(&ecosystem::PYTHON));
+        assert_eq!(commands.build.as_deref(), Some("hatch run build"));
+        assert_eq!(commands.test.as_deref(), Some("tox"));
+        assert_eq!(commands.lint.as_deref(), Some("tox -e lint"));
+        assert_eq!(commands.format.as_deref(), Some("nox -s format"));
+        assert_eq!(
+            commands.format_check.as_deref(),
+            Some("ruff format --check")
+        );
+    }
+}
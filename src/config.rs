@@ -0,0 +1,463 @@
+//! Configuration files
+//!
+//! Proact reads an optional user-level configuration from
+//! `~/.config/proact/config.toml` (or `$XDG_CONFIG_HOME/proact/config.toml`)
+//! and an optional project-level `.proact.toml` in the target directory.
+//! Project settings override user settings, and CLI flags override both.
+//!
+//! Example `.proact.toml`:
+//!
+//! ```toml
+//! output-dir = "docs"
+//! sections = ["process", "quality", "project-notes", "summary"]
+//! templates = ["team/review-checklist.md"]
+//! license = "MIT"
+//! copyright-owner = "Acme Corp"
+//! agents = ["claude", "cursor"]
+//! learnings-library = "../handbook/learnings.md"
+//! per-package = true
+//!
+//! [detectors]
+//! disabled = ["javascript"]
+//! order = ["python"]
+//!
+//! [mcp]
+//! enabled = true
+//!
+//! [mcp.servers.context7]
+//! command = "npx"
+//! args = ["-y", "@upstash/context7-mcp"]
+//! ```
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::agents::Agent;
+use crate::detector::Registry;
+use crate::generator::DocSection;
+use crate::managed::Overwrite;
+use crate::mcp::McpServer;
+
+/// File name of the project-level configuration
+pub const PROJECT_CONFIG_FILE: &str = ".proact.toml";
+
+/// Settings read from a configuration file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Output directory for generated documentation, relative to the target
+    pub output_dir: Option<PathBuf>,
+    /// Sections to include in ai_agent_instructions.md
+    pub sections: Option<Vec<DocSection>>,
+    /// Extra templates rendered into the output directory
+    pub templates: Vec<PathBuf>,
+    /// License to use instead of the one detected from project files
+    pub license: Option<String>,
+    /// Copyright owner to use instead of the git user name
+    pub copyright_owner: Option<String>,
+    /// Agents to generate entry files for
+    pub agents: Option<Vec<Agent>>,
+    /// Organization-wide learnings.md used instead of the embedded seed
+    pub learnings_library: Option<PathBuf>,
+    /// Write agent instructions into each workspace package
+    pub per_package: Option<bool>,
+    /// Project detector configuration
+    pub detectors: DetectorsConfig,
+    /// MCP server configuration
+    pub mcp: McpConfig,
+}
+
+/// The `[detectors]` table
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DetectorsConfig {
+    /// Detectors that do not run
+    pub disabled: Vec<String>,
+    /// Detectors that run first, in this order
+    pub order: Vec<String>,
+}
+
+/// The `[mcp]` table
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpConfig {
+    /// Write MCP configuration files even without --mcp
+    pub enabled: Option<bool>,
+    /// Additional servers keyed by name
+    pub servers: BTreeMap<String, McpServerConfig>,
+}
+
+/// A server in the `[mcp.servers.NAME]` table
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct McpServerConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl Config {
+    /// Load and merge the user-level and project-level configuration
+    pub fn load(target_path: &Path) -> Result<Self> {
+        let user = match user_config_path() {
+            Some(path) => Self::load_file(&path)?,
+            None => None,
+        };
+        let project = Self::load_file(&target_path.join(PROJECT_CONFIG_FILE))?;
+
+        Ok(match (user, project) {
+            (Some(user), Some(project)) => user.merge(project),
+            (user, project) => project.or(user).unwrap_or_default(),
+        })
+    }
+
+    /// Load a single configuration file, returning None if it does not exist
+    ///
+    /// Relative template and learnings library paths are resolved against
+    /// the file's directory.
+    pub fn load_file(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut config: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid configuration in {}", path.display()))?;
+
+        let base = path.parent().unwrap_or(Path::new("."));
+        for path in config
+            .templates
+            .iter_mut()
+            .chain(config.learnings_library.as_mut())
+        {
+            if path.is_relative() {
+                *path = base.join(&*path);
+            }
+        }
+
+        Ok(Some(config))
+    }
+
+    /// Overlay another configuration on top of this one
+    ///
+    /// Values set in `other` win; MCP servers are merged by name.
+    pub fn merge(self, other: Self) -> Self {
+        let mut servers = self.mcp.servers;
+        servers.extend(other.mcp.servers);
+
+        Self {
+            output_dir: other.output_dir.or(self.output_dir),
+            sections: other.sections.or(self.sections),
+            templates: if other.templates.is_empty() {
+                self.templates
+            } else {
+                other.templates
+            },
+            license: other.license.or(self.license),
+            copyright_owner: other.copyright_owner.or(self.copyright_owner),
+            agents: other.agents.or(self.agents),
+            learnings_library: other.learnings_library.or(self.learnings_library),
+            per_package: other.per_package.or(self.per_package),
+            detectors: DetectorsConfig {
+                disabled: if other.detectors.disabled.is_empty() {
+                    self.detectors.disabled
+                } else {
+                    other.detectors.disabled
+                },
+                order: if other.detectors.order.is_empty() {
+                    self.detectors.order
+                } else {
+                    other.detectors.order
+                },
+            },
+            mcp: McpConfig {
+                enabled: other.mcp.enabled.or(self.mcp.enabled),
+                servers,
+            },
+        }
+    }
+
+    /// The built-in project detectors, ordered and disabled as configured
+    pub fn detectors(&self) -> Result<Registry> {
+        let mut registry = Registry::builtin();
+        registry.prioritize(&self.detectors.order)?;
+        for name in &self.detectors.disabled {
+            registry.disable(name)?;
+        }
+        Ok(registry)
+    }
+
+    /// MCP servers declared in the configuration
+    pub fn mcp_servers(&self) -> Vec<McpServer> {
+        self.mcp
+            .servers
+            .iter()
+            .map(|(name, server)| McpServer {
+                name: name.clone(),
+                command: server.command.clone(),
+                args: server.args.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Settings given on the command line, which override the configuration
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    /// Output directory, relative to the target
+    pub output_dir: Option<PathBuf>,
+    /// Agents to generate entry files for; the configured ones when empty
+    pub agents: Vec<Agent>,
+    /// Write MCP server configuration even if the configuration does not
+    pub mcp: bool,
+    /// Additional MCP servers, as `NAME=COMMAND [ARGS...]`
+    pub mcp_servers: Vec<String>,
+    /// Replace differing legal files
+    pub force: bool,
+    /// Back up differing legal files before replacing them
+    pub backup: bool,
+    /// Write agent instructions into each workspace package
+    pub per_package: bool,
+}
+
+/// Settings for a run, resolved from configuration files and CLI flags
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Output directory (absolute, or relative to the current directory)
+    pub output_dir: PathBuf,
+    pub sections: Vec<DocSection>,
+    pub templates: Vec<PathBuf>,
+    pub agents: Vec<Agent>,
+    pub mcp_servers: Vec<McpServer>,
+    pub learnings_library: Option<PathBuf>,
+    /// Write agent instructions into each workspace package
+    pub per_package: bool,
+    /// How to handle existing legal files that differ
+    pub overwrite: Overwrite,
+    /// Detectors used to recognize the project type
+    pub detectors: Registry,
+}
+
+impl Settings {
+    /// Default settings for `target`: every section written to `docs`, no
+    /// agent files, per-package files or MCP configuration, existing legal
+    /// files kept, the built-in detectors
+    pub fn new(target: &Path) -> Self {
+        Self {
+            output_dir: target.join("docs"),
+            sections: DocSection::ALL.to_vec(),
+            templates: Vec::new(),
+            agents: Vec::new(),
+            mcp_servers: Vec::new(),
+            learnings_library: None,
+            per_package: false,
+            overwrite: Overwrite::Refuse,
+            detectors: Registry::builtin(),
+        }
+    }
+
+    /// Resolve settings for a run; CLI flags override the configuration
+    pub fn resolve(target: &Path, overrides: &Overrides, config: &Config) -> Result<Self> {
+        let output_dir = resolve_output_dir(target, overrides.output_dir.as_deref(), config);
+
+        let agents = if overrides.agents.is_empty() {
+            config.agents.clone().unwrap_or_default()
+        } else {
+            overrides.agents.clone()
+        };
+
+        // Playwright plus servers from the config file and --mcp-server
+        let mcp_enabled = overrides.mcp
+            || !overrides.mcp_servers.is_empty()
+            || config.mcp.enabled.unwrap_or(!config.mcp.servers.is_empty());
+        let mut mcp_servers = Vec::new();
+        if mcp_enabled {
+            mcp_servers.push(McpServer::playwright());
+            let declared = overrides
+                .mcp_servers
+                .iter()
+                .map(|declaration| McpServer::parse(declaration))
+                .collect::<Result<Vec<_>>>()?;
+            for server in config.mcp_servers().into_iter().chain(declared) {
+                mcp_servers.retain(|existing| existing.name != server.name);
+                mcp_servers.push(server);
+            }
+        }
+
+        Ok(Self {
+            output_dir,
+            sections: config
+                .sections
+                .clone()
+                .unwrap_or_else(|| DocSection::ALL.to_vec()),
+            templates: config.templates.clone(),
+            agents,
+            mcp_servers,
+            learnings_library: config.learnings_library.clone(),
+            per_package: overrides.per_package || config.per_package.unwrap_or(false),
+            overwrite: if overrides.force {
+                Overwrite::Force
+            } else if overrides.backup {
+                Overwrite::Backup
+            } else {
+                Overwrite::Refuse
+            },
+            detectors: config.detectors()?,
+        })
+    }
+}
+
+/// Output directory from the CLI flag, the configuration, or `docs`
+///
+/// Relative paths are resolved against the target directory.
+pub fn resolve_output_dir(target: &Path, cli: Option<&Path>, config: &Config) -> PathBuf {
+    let output_dir = cli
+        .or(config.output_dir.as_deref())
+        .unwrap_or(Path::new("docs"));
+    target.join(output_dir)
+}
+
+/// Location of the user-level configuration file
+fn user_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("proact").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_config() {
+        let config: Config = toml::from_str(
+            r#"
+output-dir = "documentation"
+sections = ["process", "summary"]
+templates = ["extra.md"]
+license = "Apache-2.0"
+copyright-owner = "Acme Corp"
+agents = ["claude", "cursor"]
+learnings-library = "/org/learnings.md"
+per-package = true
+
+[detectors]
+disabled = ["javascript"]
+order = ["python"]
+
+[mcp]
+enabled = true
+
+[mcp.servers.context7]
+command = "npx"
+args = ["-y", "@upstash/context7-mcp"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.output_dir, Some(PathBuf::from("documentation")));
+        assert_eq!(
+            config.sections,
+            Some(vec![DocSection::Process, DocSection::Summary])
+        );
+        assert_eq!(config.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(config.copyright_owner.as_deref(), Some("Acme Corp"));
+        assert_eq!(config.agents, Some(vec![Agent::Claude, Agent::Cursor]));
+        assert_eq!(
+            config.learnings_library,
+            Some(PathBuf::from("/org/learnings.md"))
+        );
+        assert_eq!(config.per_package, Some(true));
+        assert_eq!(config.mcp.enabled, Some(true));
+
+        let detectors = config.detectors().unwrap();
+        assert_eq!(detectors.enabled()[..2], ["python", "rust"]);
+        assert!(!detectors.enabled().contains(&"javascript"));
+
+        let servers = config.mcp_servers();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "context7");
+        assert_eq!(servers[0].args, vec!["-y", "@upstash/context7-mcp"]);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        assert!(toml::from_str::<Config>("outputdir = \"docs\"").is_err());
+    }
+
+    #[test]
+    fn test_unknown_detector_is_rejected() {
+        let config: Config = toml::from_str("[detectors]\ndisabled = [\"cobol\"]").unwrap();
+        let error = config.detectors().unwrap_err().to_string();
+        assert!(
+            error.contains("Unknown project detector 'cobol'"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_cli_overrides_config() {
+        let config: Config = toml::from_str(
+            r#"
+output-dir = "from-config"
+agents = ["claude"]
+[mcp.servers.context7]
+command = "npx"
+"#,
+        )
+        .unwrap();
+
+        let args = Overrides {
+            output_dir: Some(PathBuf::from("from-cli")),
+            agents: vec![Agent::Cursor],
+            ..Overrides::default()
+        };
+        let settings = Settings::resolve(Path::new("proj"), &args, &config).unwrap();
+        assert_eq!(settings.output_dir, PathBuf::from("proj/from-cli"));
+        assert_eq!(settings.agents, vec![Agent::Cursor]);
+        let names: Vec<_> = settings
+            .mcp_servers
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["playwright", "context7"]);
+
+        let args = Overrides::default();
+        let settings = Settings::resolve(Path::new("proj"), &args, &config).unwrap();
+        assert_eq!(settings.output_dir, PathBuf::from("proj/from-config"));
+        assert_eq!(settings.agents, vec![Agent::Claude]);
+        assert_eq!(settings.sections, DocSection::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_project_overrides_user() {
+        let user: Config = toml::from_str(
+            r#"
+license = "MIT"
+copyright-owner = "User"
+[mcp.servers.a]
+command = "a"
+"#,
+        )
+        .unwrap();
+        let project: Config = toml::from_str(
+            r#"
+copyright-owner = "Team"
+[mcp.servers.b]
+command = "b"
+"#,
+        )
+        .unwrap();
+
+        let merged = user.merge(project);
+        assert_eq!(merged.license.as_deref(), Some("MIT"));
+        assert_eq!(merged.copyright_owner.as_deref(), Some("Team"));
+        assert_eq!(merged.mcp.servers.len(), 2);
+    }
+}
@@ -0,0 +1,295 @@
+//! Project detectors
+//!
+//! A [`ProjectDetector`] recognizes one kind of project, reports how sure it
+//! is along with structured facts (language, marker files, frameworks,
+//! commands), and renders the guidance section for the project-specific
+//! notes. Detectors live in a [`Registry`], which decides their order and
+//! which of them are disabled, so new ecosystems can be added without
+//! touching the generator.
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::ecosystem;
+
+/// How sure a detector is that the project is of its kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// A hint only, e.g. a file other tools also use
+    Low,
+    /// A secondary marker such as `requirements.txt`
+    Medium,
+    /// The ecosystem's own manifest or build file
+    High,
+}
+
+/// The standard commands of a project
+///
+/// A role is None when the project has no command for it, e.g. a
+/// JavaScript project without a `lint` script.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Commands {
+    pub build: Option<String>,
+    pub test: Option<String>,
+    pub lint: Option<String>,
+    pub format: Option<String>,
+    pub format_check: Option<String>,
+}
+
+/// What a detector found out about the project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProjectFacts {
+    /// Project type and template flag (`rust`, `go`, ...)
+    pub project_type: String,
+    /// Display name (`Rust`, `C/C++`, ...)
+    pub language: String,
+    /// Files that identified the project, relative to its root
+    pub markers: Vec<String>,
+    /// Frameworks the project uses (`axum`, `react`, ...)
+    pub frameworks: Vec<String>,
+    /// Build/test/lint/format commands, if the detector knows them
+    pub commands: Option<Commands>,
+}
+
+/// The result of a successful detection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Detection {
+    pub confidence: Confidence,
+    pub facts: ProjectFacts,
+}
+
+/// Recognizes one kind of project
+pub trait ProjectDetector: Send + Sync {
+    /// Unique name used to order and disable the detector
+    fn name(&self) -> &str;
+
+    /// Inspect the project root; None when the project is not of this kind
+    fn detect(&self, target_path: &Path) -> Option<Detection>;
+
+    /// Guidance section for the project-specific notes (a `###` heading and
+    /// its content)
+    fn render(&self, detection: &Detection) -> String;
+}
+
+/// A detection together with the detector that made it and its guidance
+#[derive(Debug, Clone)]
+pub struct Detected {
+    pub detector: String,
+    pub detection: Detection,
+    pub guidance: String,
+}
+
+/// An ordered set of detectors
+#[derive(Clone, Default)]
+pub struct Registry {
+    detectors: Vec<Arc<dyn ProjectDetector>>,
+    disabled: Vec<String>,
+}
+
+impl Registry {
+    /// A registry without any detectors
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The detectors built into proact, in their default order
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        for ecosystem in ecosystem::ALL {
+            registry.register(*ecosystem);
+        }
+        registry
+    }
+
+    /// Add a detector after the existing ones, replacing any detector of
+    /// the same name in place
+    pub fn register(&mut self, detector: impl ProjectDetector + 'static) {
+        let detector: Arc<dyn ProjectDetector> = Arc::new(detector);
+        match self
+            .detectors
+            .iter()
+            .position(|existing| existing.name() == detector.name())
+        {
+            Some(index) => self.detectors[index] = detector,
+            None => self.detectors.push(detector),
+        }
+    }
+
+    /// Names of all registered detectors, in order
+    pub fn names(&self) -> Vec<&str> {
+        self.detectors
+            .iter()
+            .map(|detector| detector.name())
+            .collect()
+    }
+
+    /// Names of the detectors that run, in order
+    pub fn enabled(&self) -> Vec<&str> {
+        self.names()
+            .into_iter()
+            .filter(|name| !self.disabled.iter().any(|disabled| disabled == name))
+            .collect()
+    }
+
+    /// Stop a detector from running
+    pub fn disable(&mut self, name: &str) -> Result<()> {
+        self.check_name(name)?;
+        if !self.disabled.iter().any(|disabled| disabled == name) {
+            self.disabled.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    /// Move the named detectors to the front, in the given order
+    pub fn prioritize(&mut self, names: &[String]) -> Result<()> {
+        for name in names.iter().rev() {
+            self.check_name(name)?;
+            let index = self
+                .detectors
+                .iter()
+                .position(|detector| detector.name() == name)
+                .expect("check_name found the detector");
+            let detector = self.detectors.remove(index);
+            self.detectors.insert(0, detector);
+        }
+        Ok(())
+    }
+
+    /// Run the enabled detectors on the project
+    ///
+    /// Results are ordered by confidence, then by detector order. Only the
+    /// first detection of each project type is kept, so e.g. a Gradle build
+    /// wins over a `pom.xml` in the same project.
+    pub fn detect(&self, target_path: &Path) -> Vec<Detected> {
+        let mut detected: Vec<Detected> = Vec::new();
+        for detector in &self.detectors {
+            if self.disabled.iter().any(|name| name == detector.name()) {
+                continue;
+            }
+            let Some(detection) = detector.detect(target_path) else {
+                continue;
+            };
+            if detected
+                .iter()
+                .any(|found| found.detection.facts.project_type == detection.facts.project_type)
+            {
+                continue;
+            }
+            detected.push(Detected {
+                detector: detector.name().to_string(),
+                guidance: detector.render(&detection),
+                detection,
+            });
+        }
+        detected.sort_by_key(|detected| Reverse(detected.detection.confidence));
+        detected
+    }
+
+    fn check_name(&self, name: &str) -> Result<()> {
+        if !self.names().contains(&name) {
+            bail!(
+                "Unknown project detector '{name}'; known detectors: {}",
+                self.names().join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("detectors", &self.names())
+            .field("disabled", &self.disabled)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::setup_test_dir;
+    use std::fs;
+
+    /// Detects projects with a `.marker` file
+    struct MarkerDetector;
+
+    impl ProjectDetector for MarkerDetector {
+        fn name(&self) -> &str {
+            "marker"
+        }
+
+        fn detect(&self, target_path: &Path) -> Option<Detection> {
+            target_path.join(".marker").exists().then(|| Detection {
+                confidence: Confidence::Low,
+                facts: ProjectFacts {
+                    project_type: "marker".to_string(),
+                    language: "Marker".to_string(),
+                    markers: vec![".marker".to_string()],
+                    frameworks: Vec::new(),
+                    commands: None,
+                },
+            })
+        }
+
+        fn render(&self, _detection: &Detection) -> String {
+            "### Marker Projects".to_string()
+        }
+    }
+
+    fn detectors(detected: &[Detected]) -> Vec<&str> {
+        detected
+            .iter()
+            .map(|detected| detected.detector.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_custom_detector() {
+        let dir = setup_test_dir("detector", "custom");
+        fs::write(dir.join(".marker"), "").unwrap();
+        fs::write(dir.join("Cargo.toml"), "").unwrap();
+
+        let mut registry = Registry::builtin();
+        registry.register(MarkerDetector);
+        let detected = registry.detect(&dir);
+        // High confidence detections come first
+        assert_eq!(detectors(&detected), ["rust", "marker"]);
+        assert_eq!(detected[1].guidance, "### Marker Projects");
+    }
+
+    #[test]
+    fn test_disable_and_prioritize() {
+        let dir = setup_test_dir("detector", "order");
+        fs::write(dir.join("Cargo.toml"), "").unwrap();
+        fs::write(dir.join("package.json"), "{}").unwrap();
+        fs::write(dir.join("go.mod"), "").unwrap();
+
+        let mut registry = Registry::builtin();
+        registry.prioritize(&["go".to_string()]).unwrap();
+        registry.disable("javascript").unwrap();
+        assert_eq!(detectors(&registry.detect(&dir)), ["go", "rust"]);
+        assert!(!registry.enabled().contains(&"javascript"));
+
+        assert!(registry.disable("cobol").is_err());
+        assert!(registry.prioritize(&["cobol".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_confidence_orders_detections() {
+        let dir = setup_test_dir("detector", "confidence");
+        fs::write(dir.join("requirements.txt"), "").unwrap();
+        fs::write(dir.join("Gemfile"), "").unwrap();
+
+        let detected = Registry::builtin().detect(&dir);
+        assert_eq!(detectors(&detected), ["ruby", "python"]);
+        assert_eq!(detected[1].detection.confidence, Confidence::Medium);
+    }
+}
@@ -0,0 +1,103 @@
+//! Unified diff output for dry runs
+//!
+//! Renders the difference between a file on disk and the content proact
+//! would write, so a reviewer can see exactly what a regeneration changes.
+
+use similar::TextDiff;
+use std::path::Path;
+
+use crate::managed::FileChange;
+
+/// Number of unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Render a unified diff for a file change
+///
+/// Paths are shown relative to `root` in git style (`a/docs/process.md`).
+/// Returns None when the file would not be written. New files are diffed
+/// against `/dev/null`.
+pub fn unified_diff(change: &FileChange, root: &Path) -> Option<String> {
+    if !change.action.is_write() {
+        return None;
+    }
+
+    let before = change.before.as_deref().unwrap_or("");
+    let old_header = if change.before.is_some() {
+        header_path("a", &change.path, root)
+    } else {
+        "/dev/null".to_string()
+    };
+    let new_header = header_path("b", &change.path, root);
+
+    let diff = TextDiff::from_lines(before, change.after.as_str());
+    let rendered = diff
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .header(&old_header, &new_header)
+        .to_string();
+
+    Some(rendered)
+}
+
+/// Format a diff header path, prefixed like git when relative to the root
+fn header_path(prefix: &str, path: &Path, root: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => format!("{prefix}/{}", relative.display()),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managed::FileAction;
+    use std::path::PathBuf;
+
+    fn change(action: FileAction, before: Option<&str>, after: &str) -> FileChange {
+        FileChange {
+            path: PathBuf::from("/project/docs/process.md"),
+            action,
+            before: before.map(str::to_string),
+            after: after.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_unified_diff_for_new_file() {
+        let diff = unified_diff(
+            &change(FileAction::Created, None, "one\ntwo\n"),
+            Path::new("/project"),
+        )
+        .unwrap();
+        assert!(diff.starts_with("--- /dev/null\n+++ b/docs/process.md\n"));
+        assert!(diff.contains("+one\n+two\n"));
+    }
+
+    #[test]
+    fn test_unified_diff_for_replaced_file() {
+        let diff = unified_diff(
+            &change(
+                FileAction::Replaced,
+                Some("one\ntwo\nthree\n"),
+                "one\n2\nthree\n",
+            ),
+            Path::new("/project"),
+        )
+        .unwrap();
+        assert!(diff.starts_with("--- a/docs/process.md\n+++ b/docs/process.md\n"));
+        assert!(diff.contains("-two\n+2\n"));
+    }
+
+    #[test]
+    fn test_unified_diff_skips_unchanged() {
+        let unchanged = change(FileAction::Unchanged, Some("x\n"), "x\n");
+        assert!(unified_diff(&unchanged, Path::new("/project")).is_none());
+    }
+
+    #[test]
+    fn test_unified_diff_outside_root_uses_full_path() {
+        let created = change(FileAction::Created, None, "x\n");
+        let diff = unified_diff(&created, Path::new("/elsewhere")).unwrap();
+        assert!(diff.contains("+++ /project/docs/process.md\n"));
+    }
+}
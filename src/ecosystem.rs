@@ -0,0 +1,473 @@
+//! Language ecosystems proact recognizes
+//!
+//! Each ecosystem is a [`ProjectDetector`] that recognizes the project from
+//! marker files in its root and carries the standard build/test/lint/format
+//! commands (refined by [`commands::infer`]) used in the rendered templates,
+//! plus the guidelines added to the project-specific notes. Frameworks found
+//! in the ecosystem's manifest add their own guidance after the ecosystem's.
+
+use std::fs;
+use std::path::Path;
+
+use crate::commands;
+use crate::detector::{Commands, Confidence, Detection, ProjectDetector, ProjectFacts};
+use crate::framework;
+
+/// A language ecosystem and its standard commands
+#[derive(Debug, Clone, Copy)]
+pub struct Ecosystem {
+    /// Detector name (`rust`, `gradle`, ...)
+    pub name: &'static str,
+    /// Template flag and project type name (`rust`, `java`, ...)
+    pub flag: &'static str,
+    /// Display name (`Rust`, `C/C++`, ...)
+    pub language: &'static str,
+    /// Files in the project root that identify the ecosystem; `*.ext`
+    /// matches any file with that extension
+    pub markers: &'static [&'static str],
+    /// Files that suggest the ecosystem with less confidence
+    pub weak_markers: &'static [&'static str],
+    pub build: &'static str,
+    pub test: &'static str,
+    pub lint: &'static str,
+    pub format: &'static str,
+    pub format_check: &'static str,
+    /// Guidelines for the project-specific notes section
+    pub notes: &'static str,
+}
+
+impl ProjectDetector for Ecosystem {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn detect(&self, target_path: &Path) -> Option<Detection> {
+        let (confidence, markers) = [
+            (Confidence::High, self.markers),
+            (Confidence::Medium, self.weak_markers),
+        ]
+        .into_iter()
+        .map(|(confidence, markers)| (confidence, present(target_path, markers)))
+        .find(|(_, found)| !found.is_empty())?;
+
+        Some(Detection {
+            confidence,
+            facts: ProjectFacts {
+                project_type: self.flag.to_string(),
+                language: self.language.to_string(),
+                markers,
+                frameworks: framework::detect(target_path, self.flag)
+                    .into_iter()
+                    .map(|framework| framework.name.to_string())
+                    .collect(),
+                commands: Some(commands::infer(
+                    target_path,
+                    self.name,
+                    Commands {
+                        build: Some(self.build.to_string()),
+                        test: Some(self.test.to_string()),
+                        lint: Some(self.lint.to_string()),
+                        format: Some(self.format.to_string()),
+                        format_check: Some(self.format_check.to_string()),
+                    },
+                )),
+            },
+        })
+    }
+
+    /// The ecosystem's notes followed by the guidance for each detected
+    /// framework
+    fn render(&self, detection: &Detection) -> String {
+        let mut sections = vec![self.notes];
+        sections.extend(
+            framework::ALL
+                .into_iter()
+                .filter(|framework| {
+                    detection
+                        .facts
+                        .frameworks
+                        .iter()
+                        .any(|name| name == framework.name)
+                })
+                .map(|framework| framework.guidance),
+        );
+        sections.join("\n\n")
+    }
+}
+
+/// Every built-in ecosystem, in detection order
+///
+/// Several ecosystems may share a flag (Maven and Gradle are both `java`);
+/// only the first one present is used.
+pub const ALL: [&Ecosystem; 13] = [
+    &RUST,
+    &JAVASCRIPT,
+    &PYTHON,
+    &GO,
+    &GRADLE,
+    &MAVEN,
+    &CMAKE,
+    &MESON,
+    &DOTNET,
+    &RUBY,
+    &PHP,
+    &ELIXIR,
+    &SWIFT,
+];
+
+/// The marker files present in `dir`
+fn present(dir: &Path, markers: &[&str]) -> Vec<String> {
+    markers
+        .iter()
+        .flat_map(|marker| match marker.strip_prefix('*') {
+            Some(extension) => files_with_extension(dir, extension),
+            None if dir.join(marker).exists() => vec![marker.to_string()],
+            None => Vec::new(),
+        })
+        .collect()
+}
+
+/// Names of the files in `dir` ending in `extension` (e.g. `.sln`), sorted
+fn files_with_extension(dir: &Path, extension: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(extension))
+        .collect();
+    names.sort();
+    names
+}
+
+pub const RUST: Ecosystem = Ecosystem {
+    name: "rust",
+    flag: "rust",
+    language: "Rust",
+    markers: &["Cargo.toml"],
+    weak_markers: &[],
+    build: "cargo build",
+    test: "cargo test",
+    lint: "cargo clippy --all-targets --all-features -- -D warnings",
+    format: "cargo fmt --all",
+    format_check: "cargo fmt --all -- --check",
+    notes: r#"### Rust Development
+- Build, test, lint (clippy with warnings denied) and format with the commands listed under Project Commands
+- Use `cargo doc --open` to generate and view documentation
+- Follow Rust naming conventions (snake_case for functions/variables, CamelCase for types)
+- Ensure all public items have documentation comments
+- Use Rust 2024 edition features where applicable
+- Prefer `let-else` patterns for error handling where appropriate
+- Use workspace dependencies for multi-crate projects"#,
+};
+
+pub const JAVASCRIPT: Ecosystem = Ecosystem {
+    name: "javascript",
+    flag: "javascript",
+    language: "JavaScript",
+    markers: &["package.json"],
+    weak_markers: &[],
+    build: "npm run build",
+    test: "npm test",
+    lint: "npm run lint",
+    format: "npx prettier --write .",
+    format_check: "npx prettier --check .",
+    notes: r#"### JavaScript/Node.js Development
+- Install dependencies with the package manager that owns the lockfile; never mix package managers
+- Commit the lockfile with every dependency change
+- Build, test, lint and format with the commands listed under Project Commands
+- Follow JavaScript naming conventions (camelCase for functions/variables, PascalCase for classes)
+- Use JSDoc comments for function documentation"#,
+};
+
+pub const PYTHON: Ecosystem = Ecosystem {
+    name: "python",
+    flag: "python",
+    language: "Python",
+    markers: &["pyproject.toml"],
+    weak_markers: &["requirements.txt"],
+    build: "uv build",
+    test: "pytest",
+    lint: "ruff check",
+    format: "ruff format",
+    format_check: "ruff format --check",
+    notes: r#"### Python Development
+- Build, test, lint and format with the commands listed under Project Commands
+- Manage dependencies with the project's own tool (tox, nox, hatch, poetry or uv), never a global pip
+- Write tests for `pytest` (avoid unittest)
+- Prefer `ruff` for linting and formatting (replaces black, flake8, pylint, isort)
+- Follow PEP 8 style guidelines
+- Use type hints for all function signatures
+- Use docstrings for all functions, classes, and modules"#,
+};
+
+pub const GO: Ecosystem = Ecosystem {
+    name: "go",
+    flag: "go",
+    language: "Go",
+    markers: &["go.mod"],
+    weak_markers: &[],
+    build: "go build ./...",
+    test: "go test ./...",
+    lint: "go vet ./...",
+    format: "gofmt -w .",
+    format_check: "test -z \"$(gofmt -l .)\"",
+    notes: r#"### Go Development
+- Use `go build ./...` to compile all packages
+- Use `go test ./...` to run all tests (add `-race` to catch data races)
+- Use `go vet ./...` for static checks, and `golangci-lint run` if configured
+- Use `gofmt -w .` (or `goimports`) for formatting; `gofmt -l .` must print nothing
+- Run `go mod tidy` after adding or removing dependencies
+- Return errors instead of panicking, and wrap them with `fmt.Errorf("...: %w", err)`
+- Write table-driven tests in `_test.go` files next to the code
+- Document every exported identifier with a comment starting with its name"#,
+};
+
+pub const GRADLE: Ecosystem = Ecosystem {
+    name: "gradle",
+    flag: "java",
+    language: "Java/Kotlin",
+    markers: &["build.gradle", "build.gradle.kts"],
+    weak_markers: &[],
+    build: "./gradlew build",
+    test: "./gradlew test",
+    lint: "./gradlew check",
+    format: "./gradlew spotlessApply",
+    format_check: "./gradlew spotlessCheck",
+    notes: r#"### Java/Kotlin Development (Gradle)
+- Always use the Gradle wrapper (`./gradlew`), never a system-wide Gradle
+- Use `./gradlew build` to compile and package the project
+- Use `./gradlew test` to run tests (JUnit 5)
+- Use `./gradlew check` to run tests plus configured linters (Checkstyle, detekt, ktlint)
+- Use `./gradlew spotlessApply` for formatting if Spotless is configured
+- Declare dependency versions in a version catalog (`gradle/libs.versions.toml`)
+- Follow Java naming conventions (camelCase for methods/variables, PascalCase for classes)
+- Document public APIs with Javadoc/KDoc comments"#,
+};
+
+pub const MAVEN: Ecosystem = Ecosystem {
+    name: "maven",
+    flag: "java",
+    language: "Java",
+    markers: &["pom.xml"],
+    weak_markers: &[],
+    build: "mvn package",
+    test: "mvn test",
+    lint: "mvn verify",
+    format: "mvn spotless:apply",
+    format_check: "mvn spotless:check",
+    notes: r#"### Java Development (Maven)
+- Build, test, verify and format with the commands listed under Project Commands, which use the Maven wrapper (`./mvnw`) when the project provides one
+- Run the full `verify` lifecycle before pushing, not just `test`
+- Manage dependency versions in `<dependencyManagement>`, not per module
+- Follow Java naming conventions (camelCase for methods/variables, PascalCase for classes)
+- Document public APIs with Javadoc comments"#,
+};
+
+pub const CMAKE: Ecosystem = Ecosystem {
+    name: "cmake",
+    flag: "cpp",
+    language: "C/C++",
+    markers: &["CMakeLists.txt"],
+    weak_markers: &[],
+    build: "cmake -S . -B build && cmake --build build",
+    test: "ctest --test-dir build --output-on-failure",
+    lint: "run-clang-tidy -p build",
+    format: "git ls-files '*.c' '*.cc' '*.cpp' '*.h' '*.hpp' | xargs clang-format -i",
+    format_check: "git ls-files '*.c' '*.cc' '*.cpp' '*.h' '*.hpp' | xargs clang-format --dry-run --Werror",
+    notes: r#"### C/C++ Development (CMake)
+- Configure out of tree with `cmake -S . -B build` and build with `cmake --build build`
+- Use `ctest --test-dir build --output-on-failure` to run tests
+- Use `clang-tidy` (`run-clang-tidy -p build`) for static analysis
+- Use `clang-format` with the project's `.clang-format` for formatting
+- Build with warnings enabled (`-Wall -Wextra -Werror`) and fix every warning
+- Run tests under AddressSanitizer/UndefinedBehaviorSanitizer when available
+- Prefer RAII and smart pointers over manual `new`/`delete` in C++"#,
+};
+
+pub const MESON: Ecosystem = Ecosystem {
+    name: "meson",
+    flag: "cpp",
+    language: "C/C++",
+    markers: &["meson.build"],
+    weak_markers: &[],
+    build: "meson setup build && meson compile -C build",
+    test: "meson test -C build",
+    lint: "ninja -C build clang-tidy",
+    format: "ninja -C build clang-format",
+    format_check: "ninja -C build clang-format-check",
+    notes: r#"### C/C++ Development (Meson)
+- Configure with `meson setup build` and build with `meson compile -C build`
+- Use `meson test -C build` to run tests
+- Use `ninja -C build clang-tidy` for static analysis
+- Use `ninja -C build clang-format` with the project's `.clang-format` for formatting
+- Build with `--warnlevel=3 --werror` and fix every warning
+- Run tests with `-Db_sanitize=address,undefined` when available
+- Prefer RAII and smart pointers over manual `new`/`delete` in C++"#,
+};
+
+pub const DOTNET: Ecosystem = Ecosystem {
+    name: "dotnet",
+    flag: "dotnet",
+    language: ".NET",
+    markers: &["*.sln", "*.csproj"],
+    weak_markers: &[],
+    build: "dotnet build",
+    test: "dotnet test",
+    lint: "dotnet build -warnaserror",
+    format: "dotnet format",
+    format_check: "dotnet format --verify-no-changes",
+    notes: r#"### .NET Development
+- Use `dotnet build` to compile the solution
+- Use `dotnet test` to run all test projects
+- Treat warnings as errors (`dotnet build -warnaserror`) and keep analyzers enabled
+- Use `dotnet format` for formatting; CI runs `dotnet format --verify-no-changes`
+- Enable nullable reference types (`<Nullable>enable</Nullable>`)
+- Follow .NET naming conventions (PascalCase for public members, camelCase for locals)
+- Document public APIs with XML documentation comments"#,
+};
+
+pub const RUBY: Ecosystem = Ecosystem {
+    name: "ruby",
+    flag: "ruby",
+    language: "Ruby",
+    markers: &["Gemfile"],
+    weak_markers: &[],
+    build: "bundle install",
+    test: "bundle exec rake test",
+    lint: "bundle exec rubocop",
+    format: "bundle exec rubocop -a",
+    format_check: "bundle exec rubocop",
+    notes: r#"### Ruby Development
+- Use `bundle install` to install dependencies and commit `Gemfile.lock`
+- Run every tool through `bundle exec` so the locked versions are used
+- Use `bundle exec rake test` (or `bundle exec rspec`) to run tests
+- Use `bundle exec rubocop` for linting and `rubocop -a` for safe autocorrections
+- Follow Ruby naming conventions (snake_case for methods/variables, CamelCase for classes)
+- Document public methods with YARD comments"#,
+};
+
+pub const PHP: Ecosystem = Ecosystem {
+    name: "php",
+    flag: "php",
+    language: "PHP",
+    markers: &["composer.json"],
+    weak_markers: &[],
+    build: "composer install",
+    test: "vendor/bin/phpunit",
+    lint: "vendor/bin/phpstan analyse",
+    format: "vendor/bin/php-cs-fixer fix",
+    format_check: "vendor/bin/php-cs-fixer fix --dry-run --diff",
+    notes: r#"### PHP Development
+- Use `composer install` to install dependencies and commit `composer.lock`
+- Use `vendor/bin/phpunit` to run tests
+- Use `vendor/bin/phpstan analyse` for static analysis
+- Use `vendor/bin/php-cs-fixer fix` for formatting (PSR-12)
+- Declare `strict_types=1` and type every parameter and return value
+- Follow PSR-4 autoloading: one class per file, namespace matches the directory"#,
+};
+
+pub const ELIXIR: Ecosystem = Ecosystem {
+    name: "elixir",
+    flag: "elixir",
+    language: "Elixir",
+    markers: &["mix.exs"],
+    weak_markers: &[],
+    build: "mix compile --warnings-as-errors",
+    test: "mix test",
+    lint: "mix credo --strict",
+    format: "mix format",
+    format_check: "mix format --check-formatted",
+    notes: r#"### Elixir Development
+- Use `mix deps.get` to install dependencies and `mix compile --warnings-as-errors` to build
+- Use `mix test` to run tests
+- Use `mix credo --strict` for linting if Credo is installed
+- Use `mix format` for formatting; CI runs `mix format --check-formatted`
+- Use Dialyzer (`mix dialyzer`) with `@spec` annotations on public functions
+- Document public modules and functions with `@moduledoc` and `@doc`"#,
+};
+
+pub const SWIFT: Ecosystem = Ecosystem {
+    name: "swift",
+    flag: "swift",
+    language: "Swift",
+    markers: &["Package.swift"],
+    weak_markers: &[],
+    build: "swift build",
+    test: "swift test",
+    lint: "swiftlint",
+    format: "swift-format format --in-place --recursive .",
+    format_check: "swift-format lint --recursive .",
+    notes: r#"### Swift Development
+- Use `swift build` to compile the package
+- Use `swift test` to run tests
+- Use `swiftlint` for linting if configured
+- Use `swift-format` for formatting
+- Prefer value types (`struct`, `enum`) and `let` over `var`
+- Avoid force unwrapping (`!`); handle optionals with `guard let` or `if let`
+- Document public APIs with `///` documentation comments"#,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::Registry;
+    use crate::files::setup_test_dir;
+
+    fn detected(dir: &Path) -> Vec<String> {
+        Registry::builtin()
+            .detect(dir)
+            .into_iter()
+            .map(|detected| detected.detection.facts.language)
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_from_marker_files() {
+        for (marker, language) in [
+            ("go.mod", "Go"),
+            ("pom.xml", "Java"),
+            ("build.gradle.kts", "Java/Kotlin"),
+            ("CMakeLists.txt", "C/C++"),
+            ("meson.build", "C/C++"),
+            ("App.csproj", ".NET"),
+            ("App.sln", ".NET"),
+            ("Gemfile", "Ruby"),
+            ("composer.json", "PHP"),
+            ("mix.exs", "Elixir"),
+            ("Package.swift", "Swift"),
+        ] {
+            let dir = setup_test_dir("ecosystem", marker);
+            fs::write(dir.join(marker), "").unwrap();
+            assert_eq!(detected(&dir), [language], "{marker}");
+        }
+    }
+
+    #[test]
+    fn test_detect_keeps_one_ecosystem_per_flag() {
+        let dir = setup_test_dir("ecosystem", "gradle-and-maven");
+        fs::write(dir.join("build.gradle"), "").unwrap();
+        fs::write(dir.join("pom.xml"), "").unwrap();
+        fs::write(dir.join("Cargo.toml"), "").unwrap();
+        assert_eq!(detected(&dir), ["Rust", "Java/Kotlin"]);
+    }
+
+    #[test]
+    fn test_extension_markers_are_reported() {
+        let dir = setup_test_dir("ecosystem", "dotnet");
+        fs::create_dir_all(dir.join("Tools.csproj")).unwrap();
+        assert!(DOTNET.detect(&dir).is_none());
+
+        fs::write(dir.join("App.csproj"), "").unwrap();
+        fs::write(dir.join("App.sln"), "").unwrap();
+        let detection = DOTNET.detect(&dir).unwrap();
+        assert_eq!(detection.confidence, Confidence::High);
+        assert_eq!(detection.facts.markers, ["App.sln", "App.csproj"]);
+        assert_eq!(
+            detection.facts.commands.unwrap().test.as_deref(),
+            Some("dotnet test")
+        );
+    }
+}
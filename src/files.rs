@@ -0,0 +1,39 @@
+//! Reading project files
+//!
+//! Detection, command inference and metadata extraction all read manifests
+//! that may be missing or malformed; both simply mean "no information".
+//! Generated files are read with [`read_existing`], where only a missing
+//! file is expected.
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use toml::Table;
+
+/// Read and parse a TOML file, returning None if it is missing or invalid
+pub(crate) fn read_toml(path: &Path) -> Option<Table> {
+    toml::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Read and parse a JSON file, returning None if it is missing or invalid
+pub(crate) fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Current content of `path`, or None if it does not exist
+pub(crate) fn read_existing(path: &Path) -> Result<Option<String>> {
+    if path.exists() {
+        Ok(Some(fs::read_to_string(path)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Empty directory `test-output/<module>/<name>` for a unit test
+#[cfg(test)]
+pub(crate) fn setup_test_dir(module: &str, name: &str) -> std::path::PathBuf {
+    let dir = Path::new("test-output").join(module).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
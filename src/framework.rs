@@ -0,0 +1,463 @@
+//! Frameworks detected inside an ecosystem
+//!
+//! Frameworks are recognized from the dependencies declared in the
+//! ecosystem's manifest (`Cargo.toml`, `package.json`, `pyproject.toml` or
+//! `requirements.txt`). Each detected framework adds its own guidance to the
+//! ecosystem's section of the project-specific notes, so e.g. the Rust/WASM
+//! advice only appears in projects that use `wasm-bindgen`.
+
+use crate::files::{read_json, read_toml};
+use std::fs;
+use std::path::Path;
+use toml::{Table, Value};
+
+/// A framework and the guidance emitted when a project uses it
+#[derive(Debug, Clone, Copy)]
+pub struct Framework {
+    /// Name used in project facts and as template flag (`axum`, `react`, ...)
+    pub name: &'static str,
+    /// Flag of the ecosystem whose manifest declares the framework
+    pub ecosystem: &'static str,
+    /// Dependency names that indicate the framework
+    pub dependencies: &'static [&'static str],
+    /// Guidance section (a `###` heading and its content)
+    pub guidance: &'static str,
+}
+
+/// Every known framework, in the order their guidance is emitted
+pub const ALL: [&Framework; 17] = [
+    &WASM, &YEW, &LEPTOS, &AXUM, &TOKIO, &CLAP, &NEXT, &REACT, &SVELTE, &VITE, &EXPRESS, &JEST,
+    &VITEST, &DJANGO, &FASTAPI, &FLASK, &PYTEST,
+];
+
+/// Frameworks of the given ecosystem used by the project
+pub fn detect(target_path: &Path, ecosystem: &str) -> Vec<&'static Framework> {
+    let declared = dependencies(target_path, ecosystem);
+    ALL.into_iter()
+        .filter(|framework| framework.ecosystem == ecosystem)
+        .filter(|framework| {
+            framework
+                .dependencies
+                .iter()
+                .any(|dependency| declared.iter().any(|name| name == dependency))
+        })
+        .collect()
+}
+
+/// Names of the dependencies declared in the ecosystem's manifests
+///
+/// Missing or invalid manifests declare nothing.
+fn dependencies(target_path: &Path, ecosystem: &str) -> Vec<String> {
+    match ecosystem {
+        "rust" => read_toml(&target_path.join("Cargo.toml"))
+            .map(|manifest| cargo_dependencies(&manifest))
+            .unwrap_or_default(),
+        "javascript" => read_json(&target_path.join("package.json"))
+            .map(|manifest| npm_dependencies(&manifest))
+            .unwrap_or_default(),
+        "python" => {
+            let mut names = read_toml(&target_path.join("pyproject.toml"))
+                .map(|manifest| pyproject_dependencies(&manifest))
+                .unwrap_or_default();
+            if let Ok(requirements) = fs::read_to_string(target_path.join("requirements.txt")) {
+                names.extend(
+                    requirements
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with(['#', '-']))
+                        .map(requirement_name),
+                );
+            }
+            names
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Keys of the dependency tables of a Cargo manifest, including workspace
+/// and target-specific dependencies
+fn cargo_dependencies(manifest: &Table) -> Vec<String> {
+    let mut parents = vec![manifest];
+    parents.extend(manifest.get("workspace").and_then(Value::as_table));
+    if let Some(targets) = manifest.get("target").and_then(Value::as_table) {
+        parents.extend(targets.values().filter_map(Value::as_table));
+    }
+
+    parents
+        .into_iter()
+        .flat_map(|parent| {
+            ["dependencies", "dev-dependencies", "build-dependencies"]
+                .into_iter()
+                .filter_map(|key| parent.get(key).and_then(Value::as_table))
+        })
+        .flat_map(|table| table.keys().cloned())
+        .collect()
+}
+
+/// Keys of the dependency objects of a package.json
+fn npm_dependencies(manifest: &serde_json::Value) -> Vec<String> {
+    ["dependencies", "devDependencies", "peerDependencies"]
+        .into_iter()
+        .filter_map(|key| manifest.get(key).and_then(serde_json::Value::as_object))
+        .flat_map(|dependencies| dependencies.keys().cloned())
+        .collect()
+}
+
+/// Dependencies of a pyproject.toml: PEP 621 `[project]` dependencies and
+/// optional dependencies, `[dependency-groups]` and Poetry tables
+fn pyproject_dependencies(manifest: &Table) -> Vec<String> {
+    let mut requirements: Vec<&str> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+
+    if let Some(project) = manifest.get("project").and_then(Value::as_table) {
+        requirements.extend(strings(project.get("dependencies")));
+        if let Some(optional) = project
+            .get("optional-dependencies")
+            .and_then(Value::as_table)
+        {
+            for group in optional.values() {
+                requirements.extend(strings(Some(group)));
+            }
+        }
+    }
+    if let Some(groups) = manifest.get("dependency-groups").and_then(Value::as_table) {
+        for group in groups.values() {
+            requirements.extend(strings(Some(group)));
+        }
+    }
+
+    if let Some(poetry) = manifest
+        .get("tool")
+        .and_then(|tool| tool.get("poetry"))
+        .and_then(Value::as_table)
+    {
+        let mut tables: Vec<&Table> = ["dependencies", "dev-dependencies"]
+            .into_iter()
+            .filter_map(|key| poetry.get(key).and_then(Value::as_table))
+            .collect();
+        if let Some(groups) = poetry.get("group").and_then(Value::as_table) {
+            tables.extend(
+                groups
+                    .values()
+                    .filter_map(|group| group.get("dependencies"))
+                    .filter_map(Value::as_table),
+            );
+        }
+        names.extend(
+            tables
+                .into_iter()
+                .flat_map(|table| table.keys())
+                .map(|name| normalize_python(name)),
+        );
+    }
+
+    names.extend(requirements.into_iter().map(requirement_name));
+    names
+}
+
+/// The string items of a TOML array
+fn strings(value: Option<&Value>) -> impl Iterator<Item = &str> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+}
+
+/// Package name of a PEP 508 requirement such as `fastapi[all]>=0.110`
+fn requirement_name(requirement: &str) -> String {
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    normalize_python(&requirement[..end])
+}
+
+/// Normalized Python package name (PEP 503: lowercase, `-` separators)
+fn normalize_python(name: &str) -> String {
+    name.to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+pub const WASM: Framework = Framework {
+    name: "wasm",
+    ecosystem: "rust",
+    dependencies: &["wasm-bindgen", "web-sys", "yew"],
+    guidance: r#"### Rust/WASM Projects
+- Keep JavaScript to absolute minimum - only for WASM loading
+- All business logic must be in Rust
+- Use `wasm-bindgen` for JS interop
+- Use `wasm-pack` for building and packaging
+- Write tests in Rust using `wasm-bindgen-test`, not in JavaScript
+- Use `web-sys` for DOM manipulation from Rust
+- Minimize JS bundle size - let Rust handle the complexity"#,
+};
+
+pub const YEW: Framework = Framework {
+    name: "yew",
+    ecosystem: "rust",
+    dependencies: &["yew"],
+    guidance: r#"### Yew
+- Build and serve with `trunk serve`; release builds with `trunk build --release`
+- Prefer function components with hooks over struct components
+- Keep components small and pass data down through `Properties`
+- Derive `PartialEq` on props so unchanged components are not re-rendered
+- Test components with `wasm-bindgen-test` in a headless browser"#,
+};
+
+pub const LEPTOS: Framework = Framework {
+    name: "leptos",
+    ecosystem: "rust",
+    dependencies: &["leptos"],
+    guidance: r#"### Leptos
+- Use `cargo leptos watch` for development and `cargo leptos build --release` for releases
+- Keep the `ssr` and `hydrate` features separate; server-only code goes behind `#[cfg(feature = "ssr")]`
+- Use signals for local state and resources for async data
+- Put data access in server functions (`#[server]`) instead of client code
+- Run `leptosfmt` to format `view!` macros"#,
+};
+
+pub const AXUM: Framework = Framework {
+    name: "axum",
+    ecosystem: "rust",
+    dependencies: &["axum"],
+    guidance: r#"### Axum Web Services
+- Keep handlers thin: extract input, call domain code, map the result to a response
+- Share application state with `State<T>` (wrap shared data in `Arc`)
+- Implement `IntoResponse` for the error type instead of returning raw status codes
+- Use `tower-http` layers for tracing, timeouts, CORS and compression
+- Test routes in-process with `tower::ServiceExt::oneshot`, without binding a port"#,
+};
+
+pub const TOKIO: Framework = Framework {
+    name: "tokio",
+    ecosystem: "rust",
+    dependencies: &["tokio"],
+    guidance: r#"### Tokio Async Runtime
+- Never block the runtime: use `tokio::task::spawn_blocking` for CPU-heavy or blocking work
+- Use `tokio::fs`, `tokio::time::sleep` and async I/O instead of the std equivalents
+- Do not hold a `std::sync::Mutex` guard across `.await`
+- Write async tests with `#[tokio::test]`
+- Enable only the Tokio features the project needs"#,
+};
+
+pub const CLAP: Framework = Framework {
+    name: "clap",
+    ecosystem: "rust",
+    dependencies: &["clap"],
+    guidance: r#"### Command-Line Interface (clap)
+- Define arguments with the derive API (`#[derive(Parser)]`) and doc comments as help text
+- Keep `--help` output accurate; every flag needs a description
+- Add a test that parses representative argument lists with `Parser::try_parse_from`
+- Call `Command::debug_assert` in a test to catch invalid argument definitions
+- Exit with a non-zero status and a clear message on errors"#,
+};
+
+pub const NEXT: Framework = Framework {
+    name: "next",
+    ecosystem: "javascript",
+    dependencies: &["next"],
+    guidance: r#"### Next.js
+- Use `next dev` for development and `next build` to verify production builds
+- Default to Server Components; add `"use client"` only where interactivity is needed
+- Fetch data on the server and keep secrets out of client components
+- Use the `app/` router conventions (`layout`, `page`, `loading`, `error`) consistently
+- Run `next lint` before committing"#,
+};
+
+pub const REACT: Framework = Framework {
+    name: "react",
+    ecosystem: "javascript",
+    dependencies: &["react"],
+    guidance: r#"### React
+- Write function components with hooks; follow the Rules of Hooks
+- Keep components small and lift state only as far as needed
+- Give list items stable `key`s (never array indexes for dynamic lists)
+- Test behavior with React Testing Library, querying by role and label
+- Enable `eslint-plugin-react-hooks` to catch missing effect dependencies"#,
+};
+
+pub const SVELTE: Framework = Framework {
+    name: "svelte",
+    ecosystem: "javascript",
+    dependencies: &["svelte", "@sveltejs/kit"],
+    guidance: r#"### Svelte
+- Use `svelte-check` to type-check components before committing
+- Keep component state local; use stores only for state shared across components
+- Use SvelteKit `load` functions for data loading instead of fetching in `onMount`
+- Test components with `@testing-library/svelte`"#,
+};
+
+pub const VITE: Framework = Framework {
+    name: "vite",
+    ecosystem: "javascript",
+    dependencies: &["vite"],
+    guidance: r#"### Vite
+- Use `vite` for development and `vite build` to verify production builds
+- Expose only `VITE_`-prefixed environment variables to client code
+- Keep `vite.config` minimal and document every plugin it uses"#,
+};
+
+pub const EXPRESS: Framework = Framework {
+    name: "express",
+    ecosystem: "javascript",
+    dependencies: &["express"],
+    guidance: r#"### Express Server
+- Validate request bodies and parameters at the route boundary
+- Handle async errors by passing them to `next(err)` and use a central error middleware
+- Keep route handlers thin and move logic into testable modules
+- Test endpoints with `supertest` against the app without starting a server
+- Use `helmet` and explicit CORS configuration in production"#,
+};
+
+pub const JEST: Framework = Framework {
+    name: "jest",
+    ecosystem: "javascript",
+    dependencies: &["jest"],
+    guidance: r#"### Jest
+- Run tests with `npx jest`; use `--watch` while developing
+- Keep test files next to the code (`*.test.js`/`*.test.ts`)
+- Reset mocks between tests (`clearMocks: true`) instead of sharing state
+- Avoid snapshot tests for logic; assert on specific values"#,
+};
+
+pub const VITEST: Framework = Framework {
+    name: "vitest",
+    ecosystem: "javascript",
+    dependencies: &["vitest"],
+    guidance: r#"### Vitest
+- Run tests with `npx vitest run` (CI) or `npx vitest` (watch mode)
+- Keep test files next to the code (`*.test.ts`)
+- Restore mocks between tests (`restoreMocks: true`)
+- Use `vi.useFakeTimers()` instead of real delays in tests"#,
+};
+
+pub const DJANGO: Framework = Framework {
+    name: "django",
+    ecosystem: "python",
+    dependencies: &["django"],
+    guidance: r#"### Django
+- Run `python manage.py makemigrations --check` to catch missing migrations
+- Commit every migration and never edit one that has been applied elsewhere
+- Run tests with `python manage.py test` (or `pytest --ds=<settings>` with pytest-django)
+- Keep business logic out of views; use model methods or service modules
+- Read secrets and `DEBUG` from the environment, never from committed settings"#,
+};
+
+pub const FASTAPI: Framework = Framework {
+    name: "fastapi",
+    ecosystem: "python",
+    dependencies: &["fastapi"],
+    guidance: r#"### FastAPI
+- Run the development server with `fastapi dev` (or `uvicorn app.main:app --reload`)
+- Declare request and response models with Pydantic; set `response_model` on routes
+- Use dependencies (`Depends`) for database sessions, auth and configuration
+- Use `async def` only for handlers that await; blocking code belongs in `def` handlers
+- Test with `fastapi.testclient.TestClient` or `httpx.AsyncClient`"#,
+};
+
+pub const FLASK: Framework = Framework {
+    name: "flask",
+    ecosystem: "python",
+    dependencies: &["flask"],
+    guidance: r#"### Flask
+- Use the application factory pattern (`create_app`) and blueprints
+- Run the development server with `flask --app <module> run --debug`
+- Test with `app.test_client()` using a testing configuration
+- Read secrets from the environment, never from committed configuration"#,
+};
+
+pub const PYTEST: Framework = Framework {
+    name: "pytest",
+    ecosystem: "python",
+    dependencies: &["pytest"],
+    guidance: r#"### pytest
+- Put shared fixtures in `conftest.py` and keep them small
+- Use `pytest.mark.parametrize` instead of loops inside tests
+- Use `tmp_path` and `monkeypatch` instead of touching real files or environment
+- Configure pytest in `pyproject.toml` under `[tool.pytest.ini_options]`"#,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::setup_test_dir;
+
+    fn names(target_path: &Path, ecosystem: &str) -> Vec<&'static str> {
+        detect(target_path, ecosystem)
+            .into_iter()
+            .map(|framework| framework.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_cargo_frameworks() {
+        let dir = setup_test_dir("framework", "cargo");
+        fs::write(
+            dir.join("Cargo.toml"),
+            r#"[package]
+name = "server"
+
+[dependencies]
+axum = "0.8"
+tokio = { version = "1", features = ["full"] }
+
+[target.'cfg(target_arch = "wasm32")'.dependencies]
+wasm-bindgen = "0.2"
+
+[workspace.dependencies]
+clap = "4"
+"#,
+        )
+        .unwrap();
+        assert_eq!(names(&dir, "rust"), ["wasm", "axum", "tokio", "clap"]);
+        assert!(names(&dir, "javascript").is_empty());
+    }
+
+    #[test]
+    fn test_detect_npm_frameworks() {
+        let dir = setup_test_dir("framework", "npm");
+        fs::write(
+            dir.join("package.json"),
+            r#"{
+  "dependencies": { "next": "15.0.0", "react": "19.0.0" },
+  "devDependencies": { "vitest": "2.0.0" }
+}"#,
+        )
+        .unwrap();
+        assert_eq!(names(&dir, "javascript"), ["next", "react", "vitest"]);
+    }
+
+    #[test]
+    fn test_detect_python_frameworks() {
+        let dir = setup_test_dir("framework", "python");
+        fs::write(
+            dir.join("pyproject.toml"),
+            r#"[project]
+name = "api"
+dependencies = ["FastAPI[standard]>=0.110", "pydantic"]
+
+[dependency-groups]
+dev = ["pytest>=8"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(names(&dir, "python"), ["fastapi", "pytest"]);
+
+        let dir = setup_test_dir("framework", "requirements");
+        fs::write(
+            dir.join("requirements.txt"),
+            "# web\nDjango==5.0\n-r base.txt\n",
+        )
+        .unwrap();
+        assert_eq!(names(&dir, "python"), ["django"]);
+    }
+
+    #[test]
+    fn test_requirement_name() {
+        assert_eq!(requirement_name("fastapi[all]>=0.110"), "fastapi");
+        assert_eq!(
+            requirement_name("Flask_Login ; python_version>'3'"),
+            "flask-login"
+        );
+        assert_eq!(requirement_name("pytest"), "pytest");
+    }
+}
@@ -1,10 +1,77 @@
-use anyhow::Result;
-use chrono::Local;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use crate::commands;
+use crate::detector::{Commands, ProjectFacts, Registry};
+use crate::license;
+use crate::managed::{self, FileChange, Overwrite};
+use crate::metadata::ProjectMetadata;
+use crate::templates::{self, TemplateContext};
+use crate::workspace::{self, Package};
+
+/// A section of the generated ai_agent_instructions.md
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DocSection {
+    Process,
+    Quality,
+    ContinuousImprovement,
+    Playwright,
+    ProjectNotes,
+    Summary,
+}
+
+impl DocSection {
+    /// Every section, in document order
+    pub const ALL: [DocSection; 6] = [
+        DocSection::Process,
+        DocSection::Quality,
+        DocSection::ContinuousImprovement,
+        DocSection::Playwright,
+        DocSection::ProjectNotes,
+        DocSection::Summary,
+    ];
 
-use crate::metadata::{self, ProjectMetadata};
-use crate::templates;
+    /// Progress message shown in verbose mode
+    fn description(self) -> &'static str {
+        match self {
+            DocSection::Process => "process guidelines",
+            DocSection::Quality => "quality standards",
+            DocSection::ContinuousImprovement => "continuous improvement practices",
+            DocSection::Playwright => "Playwright MCP setup instructions",
+            DocSection::ProjectNotes => "project-specific notes",
+            DocSection::Summary => "summary",
+        }
+    }
+}
+
+/// Render a single section of ai_agent_instructions.md
+///
+/// Returns None for project-specific notes when no project type is detected
+/// and the target is not a workspace.
+pub fn render_section(
+    target_path: &Path,
+    section: DocSection,
+    detectors: &Registry,
+    verbose: bool,
+) -> Result<Option<String>> {
+    Ok(Some(match section {
+        DocSection::Process => templates::process_guidelines().to_string(),
+        DocSection::Quality => templates::quality_standards().to_string(),
+        DocSection::ContinuousImprovement => templates::continuous_improvement().to_string(),
+        DocSection::Playwright => templates::playwright_mcp_setup().to_string(),
+        DocSection::ProjectNotes => {
+            return Ok(generate_project_specific_notes(
+                target_path,
+                detectors,
+                verbose,
+            ));
+        }
+        DocSection::Summary => templates::summary().to_string(),
+    }))
+}
 
 /// Generates comprehensive AI agent documentation for a target project
 ///
@@ -20,7 +87,21 @@ use crate::templates;
 /// # Returns
 ///
 /// Returns the generated documentation as a String, or an error if generation fails.
+/// Projects are detected with the built-in detectors.
 pub fn generate_documentation(target_path: &Path, verbose: bool) -> Result<String> {
+    generate_sections(target_path, &DocSection::ALL, &Registry::builtin(), verbose)
+}
+
+/// Generates AI agent documentation containing only the given sections
+///
+/// Sections are always emitted in document order regardless of the order
+/// in which they are listed.
+pub fn generate_sections(
+    target_path: &Path,
+    sections: &[DocSection],
+    detectors: &Registry,
+    verbose: bool,
+) -> Result<String> {
     if verbose {
         eprintln!("Generating documentation for: {}", target_path.display());
     }
@@ -31,296 +112,422 @@ pub fn generate_documentation(target_path: &Path, verbose: bool) -> Result<Strin
     doc.push_str(&generate_header(target_path));
     doc.push('\n');
 
-    // Add process guidelines
-    if verbose {
-        eprintln!("  Adding process guidelines...");
-    }
-    doc.push_str(templates::process_guidelines());
-    doc.push('\n');
-
-    // Add quality standards
-    if verbose {
-        eprintln!("  Adding quality standards...");
-    }
-    doc.push_str(templates::quality_standards());
-    doc.push('\n');
-
-    // Add continuous improvement section
-    if verbose {
-        eprintln!("  Adding continuous improvement practices...");
-    }
-    doc.push_str(templates::continuous_improvement());
-    doc.push('\n');
+    for section in DocSection::ALL {
+        if !sections.contains(&section) {
+            continue;
+        }
 
-    // Add Playwright MCP setup instructions
-    if verbose {
-        eprintln!("  Adding Playwright MCP setup instructions...");
-    }
-    doc.push_str(templates::playwright_mcp_setup());
-    doc.push('\n');
+        // Project-specific notes are only added when a project type is detected
+        let Some(content) = render_section(target_path, section, detectors, verbose)? else {
+            continue;
+        };
 
-    // Add project-specific notes if applicable
-    if let Some(notes) = generate_project_specific_notes(target_path, verbose)? {
         if verbose {
-            eprintln!("  Adding project-specific notes...");
+            eprintln!("  Adding {}...", section.description());
+        }
+        doc.push_str(&content);
+        if section != DocSection::Summary {
+            doc.push('\n');
         }
-        doc.push_str(&notes);
-        doc.push('\n');
-    }
-
-    // Add summary
-    if verbose {
-        eprintln!("  Adding summary...");
     }
-    doc.push_str(templates::summary());
 
     Ok(doc)
 }
 
 /// Generates the header section with metadata
+///
+/// The header contains no timestamp so that regenerating unchanged
+/// documentation leaves the managed section untouched.
 fn generate_header(target_path: &Path) -> String {
     let project_name = target_path
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("Unknown Project");
 
-    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-
     format!(
-        r#"<!-- 
+        r#"<!--
 Generated by Proact for: {project_name}
-Generated at: {timestamp}
 -->
 "#
     )
 }
 
-/// Generates project-specific notes based on detected project characteristics
-fn generate_project_specific_notes(target_path: &Path, verbose: bool) -> Result<Option<String>> {
-    let mut notes = Vec::new();
+/// Generates project-specific notes from the guidance of each detector that
+/// recognizes the project, plus a map of its packages when it is a workspace
+fn generate_project_specific_notes(
+    target_path: &Path,
+    detectors: &Registry,
+    verbose: bool,
+) -> Option<String> {
+    let guidelines = project_guidelines(target_path, detectors, verbose)?;
+    Some(format!(
+        "## Project-Specific Guidelines\n\n{guidelines}\n\n---\n"
+    ))
+}
+
+/// Commands, project map and detector guidance for a project directory
+///
+/// Returns None when no project type is detected and the directory is not
+/// a workspace.
+fn project_guidelines(target_path: &Path, detectors: &Registry, verbose: bool) -> Option<String> {
+    let detected = detectors.detect(target_path);
+    let packages = workspace::packages(target_path);
+    if detected.is_empty() && packages.is_empty() {
+        return None;
+    }
 
-    // Check for common project files and add relevant notes
-    let cargo_toml = target_path.join("Cargo.toml");
-    let package_json = target_path.join("package.json");
-    let pyproject_toml = target_path.join("pyproject.toml");
-    let requirements_txt = target_path.join("requirements.txt");
+    // The commands of the first detection that knows them are the project's
+    let commands = detected
+        .iter()
+        .find_map(|detected| detected.detection.facts.commands.clone());
 
-    if cargo_toml.exists() {
+    let mut blocks = Vec::new();
+    if let Some(commands) = commands {
+        blocks.push(project_commands(&commands));
+    }
+    if !packages.is_empty() {
         if verbose {
-            eprintln!("    Detected Rust project");
+            eprintln!("    Found {} workspace packages", packages.len());
         }
-        notes.push(rust_specific_notes());
+        blocks.push(project_map(target_path, &packages, detectors));
     }
-
-    if package_json.exists() {
+    for detected in detected {
         if verbose {
-            eprintln!("    Detected Node.js/JavaScript project");
+            eprintln!(
+                "    Detected {} project ({:?} confidence, {})",
+                detected.detection.facts.language,
+                detected.detection.confidence,
+                detected.detection.facts.markers.join(", ")
+            );
+            if !detected.detection.facts.frameworks.is_empty() {
+                eprintln!(
+                    "      Frameworks: {}",
+                    detected.detection.facts.frameworks.join(", ")
+                );
+            }
         }
-        notes.push(javascript_specific_notes());
+        blocks.push(detected.guidance);
     }
 
-    if pyproject_toml.exists() || requirements_txt.exists() {
-        if verbose {
-            eprintln!("    Detected Python project");
+    Some(blocks.join("\n\n"))
+}
+
+/// The exact commands the project uses, for the project-specific notes
+fn project_commands(commands: &Commands) -> String {
+    let mut block = String::from(
+        "### Project Commands\nUse these exact commands; they are the ones this project uses:",
+    );
+    for (label, command) in [
+        ("Build", &commands.build),
+        ("Test", &commands.test),
+        ("Lint", &commands.lint),
+        ("Format", &commands.format),
+        ("Check formatting", &commands.format_check),
+    ] {
+        match command {
+            Some(command) => block.push_str(&format!("\n- {label}: `{command}`")),
+            None => block.push_str(&format!("\n- {label}: not configured")),
         }
-        notes.push(python_specific_notes());
     }
+    block
+}
 
-    if notes.is_empty() {
-        Ok(None)
+/// Table of the workspace packages with their languages and commands
+fn project_map(target_path: &Path, packages: &[Package], detectors: &Registry) -> String {
+    let mut map = format!(
+        r#"### Project Map
+This repository is a workspace of {} package{}. Run a package's commands
+from its directory, and keep changes to one package from breaking the others.
+
+| Package | Path | Language | Build | Test | Lint |
+|---------|------|----------|-------|------|------|"#,
+        packages.len(),
+        if packages.len() == 1 { "" } else { "s" }
+    );
+    for package in packages {
+        let detected = detectors.detect(&target_path.join(&package.path));
+        let languages: Vec<&str> = detected
+            .iter()
+            .map(|detected| detected.detection.facts.language.as_str())
+            .collect();
+        let commands = detected
+            .iter()
+            .find_map(|detected| detected.detection.facts.commands.as_ref());
+        let cell = |command: Option<&String>| match command {
+            Some(command) => format!("`{}`", command.replace('|', "\\|")),
+            None => "-".to_string(),
+        };
+        map.push_str(&format!(
+            "\n| {} | `{}` | {} | {} | {} | {} |",
+            package.name,
+            package.path,
+            if languages.is_empty() {
+                "-".to_string()
+            } else {
+                languages.join(", ")
+            },
+            cell(commands.and_then(|commands| commands.build.as_ref())),
+            cell(commands.and_then(|commands| commands.test.as_ref())),
+            cell(commands.and_then(|commands| commands.lint.as_ref())),
+        ));
+    }
+    map
+}
+
+/// Agent instructions for one package of a workspace
+///
+/// `output_dir` is the generated documentation directory; the
+/// instructions link to it relative to the package directory and add the
+/// package's own commands and guidance.
+pub fn package_instructions(
+    target_path: &Path,
+    package: &Package,
+    output_dir: &Path,
+    detectors: &Registry,
+) -> String {
+    let docs = path_from(&target_path.join(&package.path), output_dir);
+    let mut instructions = format!(
+        r#"# {name} Package Instructions
+
+This is the `{path}` package of a larger workspace. Follow the
+workspace-wide guidelines in `{docs}/ai_agent_instructions.md`,
+and run the commands below from this directory.
+"#,
+        name = package.name,
+        path = package.path,
+    );
+    if let Some(guidelines) = project_guidelines(&target_path.join(&package.path), detectors, false)
+    {
+        instructions.push('\n');
+        instructions.push_str(&guidelines);
+        instructions.push('\n');
+    }
+    instructions
+}
+
+/// `path` as seen from directory `from`, `/`-separated
+///
+/// Both are made absolute first; paths without a common root (e.g. on
+/// different Windows drives) are returned as absolute paths.
+fn path_from(from: &Path, path: &Path) -> String {
+    let (Ok(from), Ok(path)) = (std::path::absolute(from), std::path::absolute(path)) else {
+        return path.display().to_string();
+    };
+    let (from, path) = (normalize(&from), normalize(&path));
+    let common = from.iter().zip(&path).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return path.iter().collect::<PathBuf>().display().to_string();
+    }
+    let parts: Vec<String> = std::iter::repeat_n("..".to_string(), from.len() - common)
+        .chain(
+            path[common..]
+                .iter()
+                .map(|part| part.as_os_str().to_string_lossy().into_owned()),
+        )
+        .collect();
+    if parts.is_empty() {
+        ".".to_string()
     } else {
-        let mut result = String::from("## Project-Specific Guidelines\n\n");
-        result.push_str(&notes.join("\n"));
-        result.push_str("\n\n---\n");
-        Ok(Some(result))
+        parts.join("/")
     }
 }
 
-/// Returns Rust-specific development notes
-fn rust_specific_notes() -> String {
-    r#"### Rust Development
-- Use `cargo build` to compile the project
-- Use `cargo test` to run all tests
-- Use `cargo clippy --all-targets --all-features -- -D warnings` for strict linting
-- Use `cargo fmt` for code formatting
-- Use `cargo doc --open` to generate and view documentation
-- Follow Rust naming conventions (snake_case for functions/variables, CamelCase for types)
-- Ensure all public items have documentation comments
-- Use Rust 2024 edition features where applicable
-- Prefer `let-else` patterns for error handling where appropriate
-- Use workspace dependencies for multi-crate projects
-
-### Rust/WASM Projects
-- Keep JavaScript to absolute minimum - only for WASM loading
-- All business logic must be in Rust
-- Use `wasm-bindgen` for JS interop
-- Use `wasm-pack` for building and packaging
-- Write tests in Rust using `wasm-bindgen-test`, not in JavaScript
-- Use `web-sys` for DOM manipulation from Rust
-- Minimize JS bundle size - let Rust handle the complexity"#
-        .to_string()
+/// Components of an absolute path with `.` and `..` resolved lexically
+fn normalize(path: &Path) -> Vec<std::path::Component<'_>> {
+    use std::path::Component;
+    let mut components = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(components.last(), Some(Component::Normal(_))) {
+                    components.pop();
+                }
+            }
+            component => components.push(component),
+        }
+    }
+    components
 }
 
-/// Returns JavaScript/Node.js-specific development notes
-fn javascript_specific_notes() -> String {
-    r#"### JavaScript/Node.js Development
-- Use `npm install` or `yarn install` to install dependencies
-- Use `npm test` or `yarn test` to run tests
-- Use `npm run lint` or `yarn lint` for linting (if configured)
-- Use `prettier` for code formatting (if available)
-- Follow JavaScript naming conventions (camelCase for functions/variables, PascalCase for classes)
-- Use JSDoc comments for function documentation"#
-        .to_string()
+/// Names of the project types detected in the target (`rust`, `python`, ...)
+pub fn project_types(target_path: &Path, detectors: &Registry) -> Vec<String> {
+    detectors
+        .detect(target_path)
+        .into_iter()
+        .map(|detected| detected.detection.facts.project_type)
+        .collect()
 }
 
-/// Returns Python-specific development notes
-fn python_specific_notes() -> String {
-    r#"### Python Development
-- Use `uv` for dependency management (preferred over pip/conda)
-  - `uv pip install -r requirements.txt` for installing dependencies
-  - `uv venv` for virtual environment creation
-  - `uv pip compile requirements.in` for dependency locking
-- Use `pytest` to run tests (avoid unittest)
-- Use `ruff` for linting and formatting (replaces black, flake8, pylint, isort)
-  - `ruff check` for linting
-  - `ruff format` for formatting
-- Follow PEP 8 style guidelines
-- Use type hints for all function signatures
-- Use docstrings for all functions, classes, and modules"#
-        .to_string()
+/// Build the template context for a target project
+///
+/// Variables come from the project metadata and the detected project types.
+/// The commands of the first detection that knows them are used as the
+/// project's build/test/lint/format commands.
+pub fn template_context(
+    target_path: &Path,
+    metadata: &ProjectMetadata,
+    detectors: &Registry,
+) -> TemplateContext {
+    let detected = detectors.detect(target_path);
+    let facts: Vec<&ProjectFacts> = detected
+        .iter()
+        .map(|detected| &detected.detection.facts)
+        .collect();
+
+    let mut ctx = TemplateContext::new();
+    ctx.set("project_name", metadata.project_name.as_str());
+    ctx.set("license", metadata.license.as_str());
+    ctx.set(
+        "repository",
+        metadata.repository.clone().unwrap_or_default(),
+    );
+
+    let languages: Vec<&str> = facts.iter().map(|facts| facts.language.as_str()).collect();
+    ctx.set("language", languages.join(", "));
+    for facts in &facts {
+        ctx.set_flag(&facts.project_type, true);
+        for framework in &facts.frameworks {
+            ctx.set_flag(framework, true);
+        }
+    }
+
+    // Roles the project has no command for get a placeholder comment, and
+    // `has_<role>_command` is set for the others
+    let commands = facts.iter().find_map(|facts| facts.commands.as_ref());
+    for (name, command, placeholder) in [
+        (
+            "build_command",
+            commands.and_then(|commands| commands.build.as_deref()),
+            "# build the project",
+        ),
+        (
+            "test_command",
+            commands.and_then(|commands| commands.test.as_deref()),
+            "# run the project's test suite",
+        ),
+        (
+            "lint_command",
+            commands.and_then(|commands| commands.lint.as_deref()),
+            "# run the project's linter",
+        ),
+        (
+            "format_command",
+            commands.and_then(|commands| commands.format.as_deref()),
+            "# run the project's formatter",
+        ),
+        (
+            "format_check_command",
+            commands.and_then(|commands| commands.format_check.as_deref()),
+            "# check formatting",
+        ),
+    ] {
+        ctx.set(name, command.unwrap_or(placeholder));
+        ctx.set_flag(&format!("has_{name}"), command.is_some());
+    }
+
+    if ctx.is_true("javascript") {
+        ctx.set("package_manager", commands::package_manager(target_path));
+        ctx.set("update_command", commands::update_command(target_path));
+    }
+
+    // A wasm-ui directory marks a WASM frontend even without the dependency
+    if target_path.join("wasm-ui").is_dir() {
+        ctx.set_flag("wasm", true);
+    }
+    ctx.set_flag("has_prd", target_path.join("docs/prd.md").exists());
+    ctx.set_flag("has_design", target_path.join("docs/design.md").exists());
+
+    ctx
 }
 
 // Embed template files at compile time
 const PROCESS_MD_TEMPLATE: &str = include_str!("../templates/process.md");
 const TOOLS_MD_TEMPLATE: &str = include_str!("../templates/tools.md");
 
-/// Copy or append template files to output directory
-pub fn copy_templates(output_dir: &Path, verbose: bool, dry_run: bool) -> Result<()> {
-    let templates_to_copy = vec![
-        (PROCESS_MD_TEMPLATE, "process.md"),
-        (TOOLS_MD_TEMPLATE, "tools.md"),
+/// Plan template files rendered into managed sections in the output directory
+///
+/// `extra_templates` are additional template files (e.g. from `.proact.toml`)
+/// rendered into the output directory under their own file names.
+/// Returns the change to each template file.
+pub fn plan_templates(
+    output_dir: &Path,
+    ctx: &TemplateContext,
+    extra_templates: &[PathBuf],
+) -> Result<Vec<FileChange>> {
+    let mut templates_to_copy = vec![
+        (PROCESS_MD_TEMPLATE.to_string(), "process.md"),
+        (TOOLS_MD_TEMPLATE.to_string(), "tools.md"),
     ];
 
-    for (source_content, dest_name) in templates_to_copy {
-        let dest_path = output_dir.join(dest_name);
-
-        // Check if destination already exists
-        if dest_path.exists() {
-            // Append to existing file with separator
-            let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-            let separator = format!(
-                "\n\n---\n\n**The following content was added by [Proact](https://github.com/softwarewrighter/proact) on {}**\n\n",
-                timestamp
-            );
-            let existing_content = fs::read_to_string(&dest_path)?;
-            let combined = format!("{existing_content}{separator}{source_content}");
-
-            if verbose {
-                eprintln!(
-                    "append {} (existing: {} bytes + separator + new: {} bytes)",
-                    dest_path.display(),
-                    existing_content.len(),
-                    source_content.len()
-                );
-            }
-
-            if !dry_run {
-                fs::write(dest_path, combined)?;
-            }
-        } else {
-            // Write new file
-            if verbose {
-                eprintln!(
-                    "write {} ({} bytes)",
-                    dest_path.display(),
-                    source_content.len()
-                );
-            }
+    for path in extra_templates {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template {}", path.display()))?;
+        let Some(dest_name) = path.file_name().and_then(|n| n.to_str()) else {
+            bail!("Template path has no file name: {}", path.display());
+        };
+        templates_to_copy.push((content, dest_name));
+    }
 
-            if !dry_run {
-                fs::write(dest_path, source_content)?;
-            }
-        }
+    let mut results = Vec::new();
+    for (template, dest_name) in templates_to_copy {
+        let source_content = templates::render(&template, ctx)
+            .with_context(|| format!("Failed to render template {dest_name}"))?;
+        let dest_path = output_dir.join(dest_name);
+        results.push(managed::plan_managed(&dest_path, &source_content)?);
     }
 
-    Ok(())
+    Ok(results)
 }
 
-/// Generate COPYRIGHT and LICENSE files based on project metadata
-/// Files are written to the target project root directory
-pub fn generate_legal_files(
+/// Plan COPYRIGHT and LICENSE files based on project metadata
+/// Files go in the target project root directory
+///
+/// Existing legal files that differ from the generated content are kept
+/// unless `overwrite` allows replacing them. Returns the change to each
+/// legal file (and any backup). No LICENSE is planned for a license proact
+/// has no text for.
+pub fn plan_legal_files(
     target_path: &Path,
-    _output_dir: &Path,
-    verbose: bool,
-    dry_run: bool,
-) -> Result<()> {
-    let metadata = ProjectMetadata::extract(target_path)?;
+    metadata: &ProjectMetadata,
+    overwrite: Overwrite,
+) -> Result<Vec<FileChange>> {
+    let mut results = Vec::new();
 
     // Generate COPYRIGHT file in target project root
     let copyright_content = metadata.copyright_string();
     let copyright_path = target_path.join("COPYRIGHT");
-
-    if verbose {
-        eprintln!(
-            "write {} ({} bytes)",
-            copyright_path.display(),
-            copyright_content.len()
-        );
-    }
-
-    if !dry_run {
-        fs::write(copyright_path, copyright_content)?;
-    }
-
-    // Generate LICENSE file in target project root (currently only MIT supported)
-    if metadata.license == "MIT" || metadata.license == "<license>" {
-        let license_content = metadata::generate_mit_license(&metadata);
-        let license_path = target_path.join("LICENSE");
-
-        if verbose {
-            eprintln!(
-                "write {} ({} bytes)",
-                license_path.display(),
-                license_content.len()
-            );
-        }
-
-        if !dry_run {
-            fs::write(license_path, license_content)?;
+    results.extend(managed::plan_protected(
+        &copyright_path,
+        &copyright_content,
+        overwrite,
+    )?);
+
+    // Generate LICENSE (or LICENSE-MIT/LICENSE-APACHE etc. for compound
+    // SPDX expressions) in target project root
+    if let Ok(files) = license::license_files(&metadata.license) {
+        for (file_name, license) in files {
+            results.extend(managed::plan_protected(
+                &target_path.join(file_name),
+                &license.text(metadata),
+                overwrite,
+            )?);
         }
-    } else if verbose {
-        eprintln!(
-            "# License type '{}' not yet supported for auto-generation, skipping LICENSE file",
-            metadata.license
-        );
     }
 
-    Ok(())
+    Ok(results)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::files::setup_test_dir;
     use std::fs;
     use std::path::PathBuf;
 
-    /// Get test output directory path
-    fn get_test_dir(test_name: &str) -> PathBuf {
-        PathBuf::from("test-output").join(test_name)
-    }
-
-    /// Setup test directory - removes old artifacts if present
-    fn setup_test_dir(test_name: &str) -> PathBuf {
-        let dir = get_test_dir(test_name);
-        // Clean up any previous test artifacts
-        let _ = fs::remove_dir_all(&dir);
-        fs::create_dir_all(&dir).unwrap();
-        dir
-    }
-
     #[test]
     fn test_generate_documentation() {
-        let temp_dir = setup_test_dir("generate_doc");
+        let temp_dir = setup_test_dir("generator", "generate_doc");
 
         let result = generate_documentation(&temp_dir, false);
         assert!(result.is_ok());
@@ -334,6 +541,25 @@ mod tests {
         // No cleanup - leave for inspection
     }
 
+    #[test]
+    fn test_generate_sections_subset() {
+        let temp_dir = setup_test_dir("generator", "generate_sections");
+        fs::write(temp_dir.join("Cargo.toml"), "[package]").unwrap();
+
+        let doc = generate_sections(
+            &temp_dir,
+            &[DocSection::Summary, DocSection::Process],
+            &Registry::builtin(),
+            false,
+        )
+        .unwrap();
+        assert!(doc.contains("Process-Oriented Workflow"));
+        assert!(doc.contains("Expected Proactive Behaviors"));
+        assert!(!doc.contains("Quality-Oriented Development"));
+        assert!(!doc.contains("Rust Development"));
+        assert!(doc.find("Process-Oriented").unwrap() < doc.find("Expected Proactive").unwrap());
+    }
+
     #[test]
     fn test_generate_header() {
         let path = PathBuf::from("/test/project");
@@ -344,37 +570,220 @@ mod tests {
 
     #[test]
     fn test_rust_project_detection() {
-        let temp_dir = setup_test_dir("rust_detection");
+        let temp_dir = setup_test_dir("generator", "rust_detection");
         fs::write(temp_dir.join("Cargo.toml"), "[package]").unwrap();
 
-        let notes = generate_project_specific_notes(&temp_dir, false).unwrap();
+        let notes = generate_project_specific_notes(&temp_dir, &Registry::builtin(), false);
         assert!(notes.is_some());
-        assert!(notes.unwrap().contains("Rust Development"));
+        let notes = notes.unwrap();
+        assert!(notes.contains("Rust Development"));
+        assert!(!notes.contains("Rust/WASM"));
 
         // No cleanup - leave for inspection
     }
 
+    #[test]
+    fn test_framework_guidance_follows_ecosystem() {
+        let temp_dir = setup_test_dir("generator", "framework_guidance");
+        fs::write(
+            temp_dir.join("Cargo.toml"),
+            "[package]\nname = \"web\"\n\n[dependencies]\nwasm-bindgen = \"0.2\"\nclap = \"4\"\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.join("package.json"),
+            r#"{"devDependencies": {"vite": "5.0.0"}}"#,
+        )
+        .unwrap();
+
+        let notes =
+            generate_project_specific_notes(&temp_dir, &Registry::builtin(), false).unwrap();
+        let position = |heading: &str| notes.find(heading).unwrap();
+        assert!(position("### Rust Development") < position("### Rust/WASM Projects"));
+        assert!(position("### Rust/WASM Projects") < position("### Command-Line Interface"));
+        assert!(position("### Command-Line Interface") < position("### JavaScript/Node.js"));
+        assert!(position("### JavaScript/Node.js") < position("### Vite"));
+        assert!(!notes.contains("### Axum"));
+        assert!(!notes.contains("### React"));
+    }
+
     #[test]
     fn test_javascript_project_detection() {
-        let temp_dir = setup_test_dir("js_detection");
+        let temp_dir = setup_test_dir("generator", "js_detection");
         fs::write(temp_dir.join("package.json"), "{}").unwrap();
 
-        let notes = generate_project_specific_notes(&temp_dir, false).unwrap();
+        let notes = generate_project_specific_notes(&temp_dir, &Registry::builtin(), false);
         assert!(notes.is_some());
         assert!(notes.unwrap().contains("JavaScript/Node.js Development"));
 
         // No cleanup - leave for inspection
     }
 
+    #[test]
+    fn test_template_context_uses_detected_commands() {
+        let temp_dir = setup_test_dir("generator", "template_context");
+        fs::write(
+            temp_dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\n\n[dependencies]\nwasm-bindgen = \"0.2\"\n",
+        )
+        .unwrap();
+
+        let metadata = ProjectMetadata::extract(&temp_dir).unwrap();
+        let ctx = template_context(&temp_dir, &metadata, &Registry::builtin());
+        assert_eq!(ctx.get("project_name"), Some("demo"));
+        assert_eq!(ctx.get("language"), Some("Rust"));
+        assert_eq!(ctx.get("test_command"), Some("cargo test"));
+        assert!(ctx.is_true("rust"));
+        assert!(ctx.is_true("wasm"));
+        assert!(!ctx.is_true("has_prd"));
+    }
+
+    #[test]
+    fn test_rendered_process_template_describes_target() {
+        let temp_dir = setup_test_dir("generator", "render_process");
+        fs::write(
+            temp_dir.join("package.json"),
+            r#"{
+  "name": "webapp",
+  "repository": "https://github.com/acme/webapp",
+  "scripts": { "test": "jest" }
+}
+"#,
+        )
+        .unwrap();
+
+        let metadata = ProjectMetadata::extract(&temp_dir).unwrap();
+        let ctx = template_context(&temp_dir, &metadata, &Registry::builtin());
+        let rendered = templates::render(PROCESS_MD_TEMPLATE, &ctx).unwrap();
+        assert!(rendered.contains("the webapp project"));
+        assert!(rendered.contains("- **Language**: JavaScript"));
+        assert!(rendered.contains("- **Repository**: https://github.com/acme/webapp"));
+        assert!(
+            rendered.contains("# Draft the release for v0.1.0 at https://github.com/acme/webapp")
+        );
+        assert!(rendered.contains("npm test"));
+        assert!(!rendered.contains("needs-attention"));
+        assert!(!rendered.contains("wasm-pack"));
+        assert!(!rendered.contains("docs/prd.md"));
+        assert!(!rendered.contains("{{"));
+    }
+
     #[test]
     fn test_python_project_detection() {
-        let temp_dir = setup_test_dir("python_detection");
+        let temp_dir = setup_test_dir("generator", "python_detection");
         fs::write(temp_dir.join("requirements.txt"), "").unwrap();
 
-        let notes = generate_project_specific_notes(&temp_dir, false).unwrap();
+        let notes = generate_project_specific_notes(&temp_dir, &Registry::builtin(), false);
         assert!(notes.is_some());
         assert!(notes.unwrap().contains("Python Development"));
 
         // No cleanup - leave for inspection
     }
+
+    #[test]
+    fn test_go_project_detection() {
+        let temp_dir = setup_test_dir("generator", "go_detection");
+        fs::write(temp_dir.join("go.mod"), "module example.com/demo\n").unwrap();
+
+        let notes =
+            generate_project_specific_notes(&temp_dir, &Registry::builtin(), false).unwrap();
+        assert!(notes.contains("Go Development"));
+        assert!(!notes.contains("Rust Development"));
+
+        let metadata = ProjectMetadata::extract(&temp_dir).unwrap();
+        let ctx = template_context(&temp_dir, &metadata, &Registry::builtin());
+        assert_eq!(ctx.get("language"), Some("Go"));
+        assert_eq!(ctx.get("test_command"), Some("go test ./..."));
+        assert!(ctx.is_true("go"));
+        assert_eq!(project_types(&temp_dir, &Registry::builtin()), ["go"]);
+    }
+
+    #[test]
+    fn test_inferred_commands_reach_notes_and_process() {
+        let temp_dir = setup_test_dir("generator", "inferred_commands");
+        fs::write(
+            temp_dir.join("package.json"),
+            r#"{"name": "app", "scripts": {"test": "jest", "lint": "eslint ."}}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.join("yarn.lock"), "").unwrap();
+
+        let notes =
+            generate_project_specific_notes(&temp_dir, &Registry::builtin(), false).unwrap();
+        assert!(notes.contains("### Project Commands"));
+        assert!(notes.contains("- Test: `yarn test`"));
+        assert!(notes.contains("- Lint: `yarn run lint`"));
+        // No build script and no prettier dependency
+        assert!(notes.contains("- Build: not configured"));
+        assert!(notes.contains("- Format: not configured"));
+
+        let metadata = ProjectMetadata::extract(&temp_dir).unwrap();
+        let ctx = template_context(&temp_dir, &metadata, &Registry::builtin());
+        let rendered = templates::render(PROCESS_MD_TEMPLATE, &ctx).unwrap();
+        assert!(rendered.contains("yarn run lint"));
+        assert!(rendered.contains("Node.js (LTS) and yarn"));
+        assert!(rendered.contains("yarn upgrade"));
+        assert!(!rendered.contains("yarn update"));
+        assert!(!rendered.contains("npm update"));
+        // CI runs only the known commands
+        assert!(rendered.contains("      - run: yarn test\n"));
+        assert!(!rendered.contains("- run: #"));
+        // No repository URL anywhere: no repository line
+        assert!(!rendered.contains("**Repository**"));
+        assert!(!rendered.contains("npm test"));
+    }
+
+    #[test]
+    fn test_path_from() {
+        assert_eq!(
+            path_from(Path::new("ws/crates/core"), Path::new("ws/docs")),
+            "../../docs"
+        );
+        assert_eq!(
+            path_from(Path::new("ws/crates/core"), Path::new("ws/../shared/docs")),
+            "../../../shared/docs"
+        );
+        let absolute = std::path::absolute("ws/docs").unwrap();
+        assert_eq!(path_from(Path::new("ws/app"), &absolute), "../docs");
+        assert_eq!(path_from(Path::new("ws"), Path::new("ws/./")), ".");
+    }
+
+    #[test]
+    fn test_project_map_lists_workspace_packages() {
+        let temp_dir = setup_test_dir("generator", "project_map");
+        fs::write(
+            temp_dir.join("package.json"),
+            r#"{"name": "monorepo", "workspaces": ["packages/*"]}"#,
+        )
+        .unwrap();
+        // Members use the workspace root's package manager
+        fs::write(temp_dir.join("pnpm-lock.yaml"), "").unwrap();
+        fs::create_dir_all(temp_dir.join("packages/web")).unwrap();
+        fs::write(
+            temp_dir.join("packages/web/package.json"),
+            r#"{"name": "@acme/web", "scripts": {"test": "vitest run"}}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(temp_dir.join("services/api")).unwrap();
+        fs::write(
+            temp_dir.join("services/api/pyproject.toml"),
+            "[project]\nname = \"acme-api\"",
+        )
+        .unwrap();
+
+        let notes =
+            generate_project_specific_notes(&temp_dir, &Registry::builtin(), false).unwrap();
+        assert!(notes.contains("### Project Map"));
+        assert!(notes.contains("workspace of 2 packages"));
+        assert!(
+            notes.contains("| @acme/web | `packages/web` | JavaScript | - | `pnpm test` | - |")
+        );
+        assert!(notes.contains("| acme-api | `services/api` | Python |"));
+
+        // A single project has no map
+        let single = setup_test_dir("generator", "project_map_single");
+        fs::write(single.join("Cargo.toml"), "[package]\nname = \"single\"").unwrap();
+        let notes = generate_project_specific_notes(&single, &Registry::builtin(), false).unwrap();
+        assert!(!notes.contains("### Project Map"));
+    }
 }
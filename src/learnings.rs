@@ -0,0 +1,624 @@
+//! Structured learnings
+//!
+//! learnings.md holds numbered entries (`### N. Title`) alongside free-form
+//! sections. Each entry is identified by a slug of its title, so proact can
+//! merge entries from the seed learnings or an organization library into a
+//! project's learnings.md without duplicating entries the project already
+//! has. Project-local entries are preserved and all entries are renumbered
+//! in document order.
+
+use anyhow::{Result, bail};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, Config};
+use crate::files::read_existing;
+use crate::managed::{self, FileAction, FileChange};
+use crate::plan::Plan;
+
+/// Seed learnings shipped with proact
+pub const SEED: &str = include_str!("../docs/learnings.md");
+
+/// Section heading used when a project's learnings.md has no entries yet
+const ENTRIES_HEADING: &str = "## Issues Encountered and Resolutions";
+
+/// A single numbered learning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Learning {
+    /// Stable identifier derived from the title
+    pub id: String,
+    pub title: String,
+    /// Everything after the heading line up to the next heading
+    pub body: String,
+}
+
+impl Learning {
+    /// Create a learning from its title and body
+    pub fn new(title: &str, body: &str) -> Self {
+        Self {
+            id: slug(title),
+            title: title.trim().to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    /// Create a learning in the Issue / Resolution / Proactive Prevention
+    /// format used by learnings.md
+    pub fn from_parts(
+        title: &str,
+        issue: &str,
+        resolution: &str,
+        prevention: &[String],
+    ) -> Result<Self> {
+        if slug(title).is_empty() {
+            bail!("A learning needs a title");
+        }
+
+        let mut body = format!(
+            "\n**Issue**: {}\n\n**Resolution**: {}\n",
+            issue.trim(),
+            resolution.trim()
+        );
+        if !prevention.is_empty() {
+            body.push_str("\n**Proactive Prevention**:\n");
+            for item in prevention {
+                body.push_str(&format!("- {}\n", item.trim()));
+            }
+        }
+        body.push('\n');
+
+        Ok(Self::new(title, &body))
+    }
+
+    /// Render the entry with the given number
+    fn render(&self, number: usize) -> String {
+        format!("### {number}. {}\n{}", self.title, self.body)
+    }
+}
+
+/// A learnings document: entries interleaved with free-form text
+#[derive(Debug, Clone)]
+enum Block {
+    Text(String),
+    Entry(Learning),
+}
+
+/// Result of merging learnings into a file
+#[derive(Debug, Clone)]
+pub(crate) struct LearningsMerge {
+    pub change: FileChange,
+    /// Titles of the learnings that were (or would be) added
+    pub added: Vec<String>,
+}
+
+/// A learning as submitted by an agent in JSON
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LearningInput {
+    pub title: String,
+    pub issue: String,
+    pub resolution: String,
+    #[serde(default)]
+    pub prevention: Prevention,
+}
+
+/// Proactive prevention as a single string or a list of bullet points
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Prevention {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Default for Prevention {
+    fn default() -> Self {
+        Prevention::Many(Vec::new())
+    }
+}
+
+impl LearningInput {
+    pub fn into_learning(self) -> Result<Learning> {
+        let prevention = match self.prevention {
+            Prevention::One(item) => vec![item],
+            Prevention::Many(items) => items,
+        };
+        Learning::from_parts(&self.title, &self.issue, &self.resolution, &prevention)
+    }
+}
+
+/// Stable identifier for a learning title: lowercase words joined by `-`
+pub(crate) fn slug(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Parse the numbered entries of a learnings document
+pub(crate) fn parse(content: &str) -> Vec<Learning> {
+    parse_blocks(content)
+        .into_iter()
+        .filter_map(|block| match block {
+            Block::Entry(learning) => Some(learning),
+            Block::Text(_) => None,
+        })
+        .collect()
+}
+
+/// Merge the entries of `source` into `existing`
+///
+/// Without an existing document the source is used as is. Otherwise only
+/// entries whose ID is not present are inserted after the last existing
+/// entry, and all entries are renumbered. Returns the merged content and the
+/// titles of the added entries.
+pub(crate) fn merge(existing: Option<&str>, source: &str) -> (String, Vec<String>) {
+    let Some(existing) = existing else {
+        let added = parse(source).into_iter().map(|l| l.title).collect();
+        return (source.to_string(), added);
+    };
+
+    // Older proact versions wrapped learnings in a managed section
+    let existing = managed::unwrap_section(existing);
+    let mut blocks = parse_blocks(&existing);
+
+    let new_entries: Vec<Learning> = {
+        let known: Vec<String> = parse(&existing).into_iter().map(|l| l.id).collect();
+        parse(&managed::unwrap_section(source))
+            .into_iter()
+            .filter(|learning| !known.contains(&learning.id))
+            .collect()
+    };
+    let added = new_entries.iter().map(|l| l.title.clone()).collect();
+    if new_entries.is_empty() {
+        return (render_blocks(&blocks), added);
+    }
+
+    let last_entry = blocks
+        .iter()
+        .rposition(|block| matches!(block, Block::Entry(_)));
+    let insert_at = match last_entry {
+        Some(index) => {
+            if let Block::Entry(learning) = &mut blocks[index] {
+                ensure_blank_line(&mut learning.body);
+            }
+            index + 1
+        }
+        None => {
+            let mut text = String::new();
+            if let Some(Block::Text(last)) = blocks.last_mut() {
+                ensure_blank_line(last);
+            }
+            text.push_str(ENTRIES_HEADING);
+            text.push_str("\n\n");
+            blocks.push(Block::Text(text));
+            blocks.len()
+        }
+    };
+
+    let at_end = insert_at == blocks.len();
+    let new_blocks = new_entries.into_iter().map(|mut learning| {
+        learning.body = format!("{}\n\n", learning.body.trim_end());
+        Block::Entry(learning)
+    });
+    blocks.splice(insert_at..insert_at, new_blocks);
+
+    let mut merged = render_blocks(&blocks);
+    if at_end {
+        merged = format!("{}\n", merged.trim_end());
+    }
+    (merged, added)
+}
+
+/// Add a single learning to a learnings document
+///
+/// Returns None when an entry with the same title already exists. A new
+/// document gets a title and the entries section heading.
+pub(crate) fn add(existing: Option<&str>, learning: &Learning) -> Option<String> {
+    let entry = learning.render(1);
+    match existing {
+        None => Some(format!(
+            "# Learnings from Development Issues\n\n{ENTRIES_HEADING}\n\n{}\n",
+            entry.trim_end()
+        )),
+        Some(existing) if parse(existing).iter().any(|l| l.id == learning.id) => None,
+        Some(existing) => Some(merge(Some(existing), &entry).0),
+    }
+}
+
+/// Remove the entries with the given IDs and renumber the rest
+///
+/// Returns None when none of the entries is present.
+pub(crate) fn remove(existing: &str, ids: &[String]) -> Option<String> {
+    let blocks = parse_blocks(existing);
+    let kept: Vec<Block> = blocks
+        .iter()
+        .filter(|block| !matches!(block, Block::Entry(learning) if ids.contains(&learning.id)))
+        .cloned()
+        .collect();
+    if kept.len() == blocks.len() {
+        return None;
+    }
+    Some(format!("{}\n", render_blocks(&kept).trim_end()))
+}
+
+/// Plan merging learnings from `source` into the learnings file at `path`
+pub(crate) fn plan_learnings(path: &Path, source: &str, verbose: bool) -> Result<LearningsMerge> {
+    let existing = read_existing(path)?;
+
+    let (merged, added) = merge(existing.as_deref(), source);
+    if verbose && existing.is_some() {
+        for title in &added {
+            eprintln!("# New learning: {title}");
+        }
+    }
+
+    let change = managed::plan_file(path, &merged)?;
+    Ok(LearningsMerge { change, added })
+}
+
+/// Plan recording `learning` in the learnings.md in `output_dir`
+///
+/// The file is left unchanged when it already has an entry with the same
+/// title; otherwise the plan's `learnings_added` holds the title.
+pub fn plan_learning(target: &Path, output_dir: &Path, learning: &Learning) -> Result<Plan> {
+    let path = output_dir.join("learnings.md");
+    let existing = read_existing(&path)?;
+    let content = add(existing.as_deref(), learning);
+    let recorded = content.is_some();
+    let change = FileChange {
+        path,
+        action: match (&existing, recorded) {
+            (_, false) => FileAction::Unchanged,
+            (Some(_), true) => FileAction::Replaced,
+            (None, true) => FileAction::Created,
+        },
+        after: content.or_else(|| existing.clone()).unwrap_or_default(),
+        before: existing,
+    };
+    Ok(Plan {
+        target: target.to_path_buf(),
+        files: vec![change],
+        learnings_added: if recorded {
+            vec![learning.title.clone()]
+        } else {
+            Vec::new()
+        },
+        warnings: Vec::new(),
+    })
+}
+
+/// Learnings harvested from projects into a learnings library
+#[derive(Debug, Clone)]
+pub struct Harvest {
+    /// The change to the library file
+    pub plan: Plan,
+    /// Project directory and title of each learning new to the library
+    pub added: Vec<(PathBuf, String)>,
+    /// Project directories without a learnings.md
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Plan merging the learnings.md of each project in `dirs` into `library`
+///
+/// A library that does not exist yet starts from the seed learnings. The
+/// merged library is written to `output`, which may be the library itself.
+pub fn plan_harvest(library: &Path, dirs: &[PathBuf], output: &Path) -> Result<Harvest> {
+    let existing = read_existing(library)?;
+    let mut merged = existing.clone().unwrap_or_else(|| SEED.to_string());
+
+    let mut added = Vec::new();
+    let mut skipped = Vec::new();
+    for dir in dirs {
+        let project_config = Config::load(dir)?;
+        let path = config::resolve_output_dir(dir, None, &project_config).join("learnings.md");
+        let Some(project_learnings) = read_existing(&path)? else {
+            skipped.push(dir.clone());
+            continue;
+        };
+        let (next, titles) = merge(Some(&merged), &project_learnings);
+        added.extend(titles.into_iter().map(|title| (dir.clone(), title)));
+        merged = next;
+    }
+
+    let before = if output == library {
+        existing
+    } else {
+        read_existing(output)?
+    };
+    let change = FileChange {
+        path: output.to_path_buf(),
+        action: match &before {
+            None => FileAction::Created,
+            Some(before) if *before == merged => FileAction::Unchanged,
+            Some(_) => FileAction::Replaced,
+        },
+        before,
+        after: merged,
+    };
+    Ok(Harvest {
+        plan: Plan {
+            target: PathBuf::from("."),
+            files: vec![change],
+            learnings_added: added.iter().map(|(_, title)| title.clone()).collect(),
+            warnings: Vec::new(),
+        },
+        added,
+        skipped,
+    })
+}
+
+/// Split a document into entries and the text between them
+///
+/// Entries start at a `### N. Title` heading and run until the next heading
+/// of level 3 or higher. Headings inside code fences are ignored.
+fn parse_blocks(content: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut text = String::new();
+    let mut entry: Option<(String, String)> = None;
+    let mut in_fence = false;
+
+    for line in content.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+
+        let is_heading = !in_fence && is_section_heading(line);
+        let entry_title = if is_heading {
+            entry_heading(line)
+        } else {
+            None
+        };
+
+        if is_heading && let Some((title, body)) = entry.take() {
+            blocks.push(Block::Entry(Learning::new(&title, &body)));
+        }
+
+        match (entry_title, &mut entry) {
+            (Some(title), _) => {
+                if !text.is_empty() {
+                    blocks.push(Block::Text(std::mem::take(&mut text)));
+                }
+                entry = Some((title, String::new()));
+            }
+            (None, Some((_, body))) => body.push_str(line),
+            (None, None) => text.push_str(line),
+        }
+    }
+
+    if let Some((title, body)) = entry {
+        blocks.push(Block::Entry(Learning::new(&title, &body)));
+    }
+    if !text.is_empty() {
+        blocks.push(Block::Text(text));
+    }
+    blocks
+}
+
+/// Render blocks, numbering entries from 1 in document order
+fn render_blocks(blocks: &[Block]) -> String {
+    let mut number = 0;
+    blocks
+        .iter()
+        .map(|block| match block {
+            Block::Text(text) => text.clone(),
+            Block::Entry(learning) => {
+                number += 1;
+                learning.render(number)
+            }
+        })
+        .collect()
+}
+
+/// Whether the line is a markdown heading of level 1 to 3
+fn is_section_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    (1..=3).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+/// Title of a `### N. Title` entry heading
+fn entry_heading(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("### ")?;
+    let (number, title) = rest.split_once(". ")?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Make the text end with an empty line so a heading can follow
+fn ensure_blank_line(text: &mut String) {
+    if text.is_empty() || text.ends_with("\n\n") {
+        return;
+    }
+    text.push_str(if text.ends_with('\n') { "\n" } else { "\n\n" });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::setup_test_dir;
+    use std::fs;
+
+    const SOURCE: &str = "# Learnings\n\n## Issues Encountered and Resolutions\n\n\
+        ### 1. Unused Imports\n\n**Issue**: unused\n\n\
+        ### 2. Edition Specification\n\n**Issue**: edition\n\n\
+        ## General Patterns\n\nRun the checkpoint.\n";
+
+    #[test]
+    fn test_slug_is_stable() {
+        assert_eq!(
+            slug("Needless Borrows (clippy::needless-borrows)"),
+            "needless-borrows-clippy-needless-borrows"
+        );
+    }
+
+    #[test]
+    fn test_parse_entries() {
+        let entries = parse(SOURCE);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "unused-imports");
+        assert_eq!(entries[1].title, "Edition Specification");
+        assert_eq!(entries[1].body, "\n**Issue**: edition\n\n");
+    }
+
+    #[test]
+    fn test_parse_ignores_headings_in_code_fences() {
+        let content = "### 1. Fences\n\n```markdown\n### 2. Not an entry\n```\n";
+        let entries = parse(content);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].body.contains("### 2. Not an entry"));
+    }
+
+    #[test]
+    fn test_merge_into_new_file_uses_source() {
+        let (merged, added) = merge(None, SOURCE);
+        assert_eq!(merged, SOURCE);
+        assert_eq!(added, vec!["Unused Imports", "Edition Specification"]);
+    }
+
+    #[test]
+    fn test_merge_is_idempotent() {
+        let (merged, added) = merge(Some(SOURCE), SOURCE);
+        assert_eq!(merged, SOURCE);
+        assert!(added.is_empty());
+    }
+
+    #[test]
+    fn test_merge_adds_only_new_entries_and_renumbers() {
+        let existing = "# Project Learnings\n\n\
+            ### 1. Flaky Browser Tests\n\nWait for network idle.\n\n\
+            ### 2. Unused Imports\n\nOur own wording.\n\n\
+            ## Notes\n\nLocal notes.\n";
+
+        let (merged, added) = merge(Some(existing), SOURCE);
+        assert_eq!(added, vec!["Edition Specification"]);
+        assert!(merged.contains("### 1. Flaky Browser Tests\n"));
+        assert!(merged.contains("### 2. Unused Imports\n\nOur own wording.\n"));
+        assert!(merged.contains("### 3. Edition Specification\n\n**Issue**: edition\n\n## Notes"));
+        assert!(merged.ends_with("## Notes\n\nLocal notes.\n"));
+        assert_eq!(merged.matches("Unused Imports").count(), 1);
+    }
+
+    #[test]
+    fn test_remove_undoes_merge() {
+        let existing = "# Project Learnings\n\n\
+            ### 1. Flaky Browser Tests\n\nWait for network idle.\n\n\
+            ### 2. Unused Imports\n\nOur own wording.\n\n\
+            ## Notes\n\nLocal notes.\n";
+
+        let (merged, _) = merge(Some(existing), SOURCE);
+        let ids = vec!["edition-specification".to_string()];
+        assert_eq!(remove(&merged, &ids).unwrap(), existing);
+        assert!(remove(existing, &ids).is_none());
+    }
+
+    #[test]
+    fn test_merge_without_entries_appends_section() {
+        let existing = "# Existing Learnings\n\nSome notes.\n";
+        let (merged, added) = merge(Some(existing), SOURCE);
+        assert_eq!(added.len(), 2);
+        assert!(merged.starts_with(
+            "# Existing Learnings\n\nSome notes.\n\n## Issues Encountered and Resolutions\n\n### 1. Unused Imports\n"
+        ));
+        assert!(merged.ends_with("**Issue**: edition\n"));
+
+        let (again, added) = merge(Some(&merged), SOURCE);
+        assert_eq!(again, merged);
+        assert!(added.is_empty());
+    }
+
+    #[test]
+    fn test_from_parts_matches_learnings_format() {
+        let learning = Learning::from_parts(
+            "Flaky Tests",
+            "Tests failed randomly",
+            "Wait for network idle",
+            &["Never sleep in tests".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            learning.render(3),
+            "### 3. Flaky Tests\n\n**Issue**: Tests failed randomly\n\n\
+             **Resolution**: Wait for network idle\n\n\
+             **Proactive Prevention**:\n- Never sleep in tests\n\n"
+        );
+        assert!(Learning::from_parts(" ", "i", "r", &[]).is_err());
+    }
+
+    #[test]
+    fn test_add_appends_and_dedupes_by_title() {
+        let learning = Learning::from_parts("Flaky Tests", "i", "r", &[]).unwrap();
+
+        let added = add(Some(SOURCE), &learning).unwrap();
+        assert!(added.contains("### 2. Edition Specification"));
+        assert!(added.contains("### 3. Flaky Tests\n\n**Issue**: i\n"));
+        assert!(added.ends_with("## General Patterns\n\nRun the checkpoint.\n"));
+
+        let duplicate = Learning::from_parts("flaky  tests", "other", "r", &[]).unwrap();
+        assert!(add(Some(&added), &duplicate).is_none());
+
+        let created = add(None, &learning).unwrap();
+        assert!(created.contains("## Issues Encountered and Resolutions\n\n### 1. Flaky Tests"));
+    }
+
+    #[test]
+    fn test_learning_input_from_json() {
+        let input: LearningInput = serde_json::from_str(
+            r#"{"title": "T", "issue": "I", "resolution": "R", "prevention": "P"}"#,
+        )
+        .unwrap();
+        let learning = input.into_learning().unwrap();
+        assert!(learning.body.contains("- P\n"));
+    }
+
+    #[test]
+    fn test_merge_unwraps_legacy_managed_section() {
+        let (legacy, _) = managed::merge(Some("# Notes\n"), SOURCE);
+        let (merged, added) = merge(Some(&legacy), SOURCE);
+        assert!(added.is_empty());
+        assert!(!merged.contains(managed::BEGIN_MARKER));
+        assert!(merged.starts_with("# Notes\n"));
+        assert_eq!(merged.matches("### 1. Unused Imports").count(), 1);
+    }
+
+    #[test]
+    fn test_plan_learning_records_once() {
+        let target = setup_test_dir("learnings", "record");
+        let output_dir = target.join("docs");
+        let learning = Learning::from_parts("Stale Lockfile", "drift", "update", &[]).unwrap();
+
+        let plan = plan_learning(&target, &output_dir, &learning).unwrap();
+        assert_eq!(plan.files[0].action, FileAction::Created);
+        assert_eq!(plan.learnings_added, ["Stale Lockfile"]);
+        assert!(!output_dir.exists());
+        plan.apply().unwrap();
+
+        let again = plan_learning(&target, &output_dir, &learning).unwrap();
+        assert_eq!(again.files[0].action, FileAction::Unchanged);
+        assert!(again.learnings_added.is_empty());
+    }
+
+    #[test]
+    fn test_plan_harvest_merges_projects() {
+        let root = setup_test_dir("learnings", "harvest");
+        let project = root.join("project");
+        fs::create_dir_all(project.join("docs")).unwrap();
+        fs::write(project.join("docs").join("learnings.md"), SOURCE).unwrap();
+        let library = root.join("library.md");
+
+        let harvest =
+            plan_harvest(&library, &[project.clone(), root.join("empty")], &library).unwrap();
+        assert_eq!(harvest.skipped, [root.join("empty")]);
+        assert_eq!(harvest.plan.files[0].action, FileAction::Created);
+        assert!(
+            harvest.plan.files[0]
+                .after
+                .contains("Edition Specification")
+        );
+        assert!(harvest.added.iter().all(|(dir, _)| *dir == project));
+        assert!(!library.exists());
+    }
+}
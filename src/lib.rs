@@ -0,0 +1,53 @@
+//! Proact: documentation for AI coding agents
+//!
+//! The `proact` binary is a thin wrapper around this library. Tooling that
+//! embeds proact can render the agent instructions directly, or plan a full
+//! generation run, inspect the intended file operations and apply them:
+//!
+//! ```no_run
+//! use proact::{Plan, ProjectMetadata, Settings};
+//! use std::path::Path;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let target = Path::new("../my-project");
+//! let metadata = ProjectMetadata::extract(target)?;
+//! let plan = Plan::new(target, &Settings::new(target), &metadata, false)?;
+//! for change in &plan.files {
+//!     println!("{:?} {}", change.action, change.path.display());
+//! }
+//! plan.apply()?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod agents;
+pub mod clean;
+mod commands;
+mod config;
+pub mod detector;
+mod diff;
+mod ecosystem;
+mod files;
+mod framework;
+mod generator;
+pub mod learnings;
+mod license;
+mod managed;
+pub mod manifest;
+mod mcp;
+mod metadata;
+mod plan;
+pub mod report;
+mod templates;
+mod workspace;
+
+pub use agents::Agent;
+pub use config::{Config, Overrides, Settings, resolve_output_dir};
+pub use detector::{Detection, ProjectDetector, ProjectFacts, Registry};
+pub use generator::{
+    DocSection, generate_documentation, generate_sections, project_types, render_section,
+};
+pub use managed::{FileAction, FileChange, Overwrite};
+pub use mcp::McpServer;
+pub use metadata::ProjectMetadata;
+pub use plan::{Execution, Operation, Plan};
@@ -0,0 +1,274 @@
+//! License files from SPDX expressions
+//!
+//! Maps the license declared in project metadata (an SPDX expression such as
+//! `MIT`, `Apache-2.0` or `MIT OR Apache-2.0`) to the license files proact
+//! writes. A single license produces `LICENSE`; a compound expression
+//! produces one file per license (`LICENSE-MIT`, `LICENSE-APACHE`), the
+//! convention used by dual-licensed Rust projects.
+
+use anyhow::{Result, bail};
+
+use crate::metadata::{self, ProjectMetadata};
+
+/// Full text of the Apache License 2.0
+const APACHE_2_0_TEXT: &str = include_str!("../templates/licenses/Apache-2.0.txt");
+
+/// Full text of the Mozilla Public License 2.0
+const MPL_2_0_TEXT: &str = include_str!("../templates/licenses/MPL-2.0.txt");
+
+/// Placeholder license used when no license is declared
+pub const PLACEHOLDER: &str = "<license>";
+
+/// A license whose text proact can generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum License {
+    Mit,
+    Apache2,
+    Bsd2Clause,
+    Bsd3Clause,
+    Isc,
+    Mpl2,
+}
+
+impl License {
+    /// Every supported license
+    pub const ALL: [License; 6] = [
+        License::Mit,
+        License::Apache2,
+        License::Bsd2Clause,
+        License::Bsd3Clause,
+        License::Isc,
+        License::Mpl2,
+    ];
+
+    /// Look up a license by SPDX identifier (case-insensitive)
+    pub fn from_spdx(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|license| license.spdx_id().eq_ignore_ascii_case(id))
+    }
+
+    /// SPDX identifier
+    pub fn spdx_id(self) -> &'static str {
+        match self {
+            License::Mit => "MIT",
+            License::Apache2 => "Apache-2.0",
+            License::Bsd2Clause => "BSD-2-Clause",
+            License::Bsd3Clause => "BSD-3-Clause",
+            License::Isc => "ISC",
+            License::Mpl2 => "MPL-2.0",
+        }
+    }
+
+    /// File name used when the project has more than one license
+    pub fn file_name(self) -> &'static str {
+        match self {
+            License::Mit => "LICENSE-MIT",
+            License::Apache2 => "LICENSE-APACHE",
+            License::Bsd2Clause => "LICENSE-BSD-2-CLAUSE",
+            License::Bsd3Clause => "LICENSE-BSD-3-CLAUSE",
+            License::Isc => "LICENSE-ISC",
+            License::Mpl2 => "LICENSE-MPL",
+        }
+    }
+
+    /// Full license text with the copyright notice filled in where the
+    /// license carries one
+    pub fn text(self, metadata: &ProjectMetadata) -> String {
+        let copyright = metadata.copyright_string();
+        match self {
+            License::Mit => metadata::generate_mit_license(metadata),
+            License::Apache2 => APACHE_2_0_TEXT.to_string(),
+            License::Mpl2 => MPL_2_0_TEXT.to_string(),
+            License::Bsd2Clause => {
+                format!("BSD 2-Clause License\n\n{copyright}\n\n{BSD_CONDITIONS}\n{BSD_DISCLAIMER}")
+            }
+            License::Bsd3Clause => format!(
+                "BSD 3-Clause License\n\n{copyright}\n\n{BSD_CONDITIONS}\n{BSD_THIRD_CLAUSE}\n{BSD_DISCLAIMER}"
+            ),
+            License::Isc => format!("ISC License\n\n{copyright}\n\n{ISC_TERMS}"),
+        }
+    }
+}
+
+/// Parse an SPDX license expression into the licenses it references
+///
+/// Supports `OR`, `AND`, parentheses and the legacy Cargo `/` separator.
+/// Fails on identifiers without an embedded text and on `WITH` exceptions.
+pub fn parse_expression(expression: &str) -> Result<Vec<License>> {
+    let normalized = expression.replace(['(', ')'], " ").replace('/', " OR ");
+    let mut licenses = Vec::new();
+
+    for token in normalized.split_whitespace() {
+        if token.eq_ignore_ascii_case("OR") || token.eq_ignore_ascii_case("AND") {
+            continue;
+        }
+        if token.eq_ignore_ascii_case("WITH") {
+            bail!("license exceptions are not supported: {expression}");
+        }
+        let Some(license) = License::from_spdx(token.trim_end_matches('+')) else {
+            bail!("no embedded text for license '{token}'");
+        };
+        if !licenses.contains(&license) {
+            licenses.push(license);
+        }
+    }
+
+    if licenses.is_empty() {
+        bail!("empty license expression");
+    }
+    Ok(licenses)
+}
+
+/// License files to write for the declared license, as (file name, license)
+///
+/// The placeholder license defaults to MIT.
+pub fn license_files(expression: &str) -> Result<Vec<(&'static str, License)>> {
+    if expression == PLACEHOLDER {
+        return Ok(vec![("LICENSE", License::Mit)]);
+    }
+    if expression.starts_with("SEE LICENSE IN ") {
+        bail!("project uses a custom license file ({expression})");
+    }
+
+    let licenses = parse_expression(expression)?;
+    Ok(match licenses.as_slice() {
+        [license] => vec![("LICENSE", *license)],
+        _ => licenses
+            .into_iter()
+            .map(|license| (license.file_name(), license))
+            .collect(),
+    })
+}
+
+const BSD_CONDITIONS: &str = r#"Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the following conditions are met:
+
+1. Redistributions of source code must retain the above copyright notice, this
+   list of conditions and the following disclaimer.
+
+2. Redistributions in binary form must reproduce the above copyright notice,
+   this list of conditions and the following disclaimer in the documentation
+   and/or other materials provided with the distribution.
+"#;
+
+const BSD_DISCLAIMER: &str = r#"THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+"#;
+
+const BSD_THIRD_CLAUSE: &str = r#"3. Neither the name of the copyright holder nor the names of its
+   contributors may be used to endorse or promote products derived from
+   this software without specific prior written permission.
+"#;
+
+const ISC_TERMS: &str = r#"Permission to use, copy, modify, and/or distribute this software for any
+purpose with or without fee is hereby granted, provided that the above
+copyright notice and this permission notice appear in all copies.
+
+THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> ProjectMetadata {
+        ProjectMetadata {
+            project_name: "test".to_string(),
+            current_year: "2025".to_string(),
+            author_name: "Test Author".to_string(),
+            author_email: None,
+            license: "MIT".to_string(),
+            repository: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_single_license() {
+        assert_eq!(
+            parse_expression("Apache-2.0").unwrap(),
+            vec![License::Apache2]
+        );
+        assert_eq!(parse_expression("isc").unwrap(), vec![License::Isc]);
+    }
+
+    #[test]
+    fn test_parse_dual_license_expressions() {
+        let expected = vec![License::Mit, License::Apache2];
+        assert_eq!(parse_expression("MIT OR Apache-2.0").unwrap(), expected);
+        assert_eq!(parse_expression("(MIT OR Apache-2.0)").unwrap(), expected);
+        assert_eq!(parse_expression("MIT/Apache-2.0").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_licenses_and_exceptions() {
+        assert!(parse_expression("GPL-3.0-only").is_err());
+        assert!(parse_expression("Apache-2.0 WITH LLVM-exception").is_err());
+        assert!(parse_expression("").is_err());
+    }
+
+    #[test]
+    fn test_license_files_for_dual_license() {
+        let files = license_files("MIT OR Apache-2.0").unwrap();
+        assert_eq!(
+            files,
+            vec![
+                ("LICENSE-MIT", License::Mit),
+                ("LICENSE-APACHE", License::Apache2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_license_files_for_single_and_placeholder() {
+        assert_eq!(
+            license_files("BSD-3-Clause").unwrap(),
+            vec![("LICENSE", License::Bsd3Clause)]
+        );
+        assert_eq!(
+            license_files(PLACEHOLDER).unwrap(),
+            vec![("LICENSE", License::Mit)]
+        );
+        assert!(license_files("SEE LICENSE IN LICENSE.txt").is_err());
+    }
+
+    #[test]
+    fn test_license_texts() {
+        let metadata = metadata();
+
+        let bsd2 = License::Bsd2Clause.text(&metadata);
+        assert!(bsd2.starts_with("BSD 2-Clause License\n\nCopyright (c) 2025 Test Author"));
+        assert!(!bsd2.contains("3. Neither"));
+
+        let bsd3 = License::Bsd3Clause.text(&metadata);
+        assert!(bsd3.contains("3. Neither the name of the copyright holder"));
+        assert!(bsd3.find("3. Neither") < bsd3.find("THIS SOFTWARE"));
+
+        assert!(
+            License::Isc
+                .text(&metadata)
+                .contains("Copyright (c) 2025 Test Author")
+        );
+        assert!(License::Apache2.text(&metadata).contains("Apache License"));
+        assert!(
+            License::Mpl2
+                .text(&metadata)
+                .starts_with("Mozilla Public License Version 2.0")
+        );
+        assert!(License::Mit.text(&metadata).starts_with("MIT License"));
+    }
+}
@@ -1,71 +1,151 @@
 mod cli;
-mod generator;
-mod metadata;
-mod templates;
 
-use anyhow::Result;
-use chrono::Local;
+use anyhow::{Context, Result};
 use clap::Parser;
-use std::fs;
+use proact::manifest::{self, BuildInfo, Manifest};
+use proact::report::{FileReport, OutputFormat, Report};
+use proact::{
+    Config, Execution, FileAction, FileChange, Plan, ProjectMetadata, Settings, clean, learnings,
+    project_types, render_section, resolve_output_dir,
+};
 use std::path::Path;
 
-/// Handles copying or appending learnings.md to the target directory
-/// Returns Some(true) if appended, Some(false) if created new, None if skipped
-fn handle_learnings_file(output_dir: &Path, dry_run: bool, verbose: bool) -> Result<Option<bool>> {
-    // Path to our source learnings.md
-    let source_learnings = Path::new("docs/learnings.md");
+/// Record a single learning in the project's learnings.md
+fn run_learn(args: &cli::LearnArgs, format: OutputFormat) -> Result<()> {
+    let learning = if args.json {
+        let input: learnings::LearningInput = serde_json::from_reader(std::io::stdin())
+            .context("Failed to read learning JSON from stdin")?;
+        input.into_learning()?
+    } else {
+        learnings::Learning::from_parts(
+            args.title.as_deref().unwrap_or_default(),
+            args.issue.as_deref().unwrap_or_default(),
+            args.resolution.as_deref().unwrap_or_default(),
+            &args.prevention,
+        )?
+    };
 
-    if !source_learnings.exists() {
-        if verbose {
-            eprintln!("# No learnings.md file found in docs/, skipping");
-        }
-        return Ok(None);
-    }
-
-    let target_learnings = output_dir.join("learnings.md");
-    let source_content = fs::read_to_string(source_learnings)?;
-
-    // Check if target exists (even in dry-run, reading is OK)
-    let target_exists = target_learnings.exists();
-
-    if target_exists {
-        // Will append to existing file with timestamp separator
-        let timestamp = Local::now().format("%Y%m%dT%H%M%S").to_string();
-        let separator = format!("\n\n---- Added {timestamp} ----\n\n");
-
-        if verbose {
-            let existing_size = fs::metadata(&target_learnings)
-                .map(|m| m.len())
-                .unwrap_or(0);
-            eprintln!(
-                "append {} (existing: {} bytes + separator + new: {} bytes)",
-                target_learnings.display(),
-                existing_size,
-                source_content.len()
-            );
-        }
+    let config = Config::load(&args.target)?;
+    let output_dir = resolve_output_dir(&args.target, args.output_dir.as_deref(), &config);
+    let plan = learnings::plan_learning(&args.target, &output_dir, &learning)?;
+    let recorded = !plan.learnings_added.is_empty();
 
-        if !dry_run {
-            let existing_content = fs::read_to_string(&target_learnings)?;
-            let combined = format!("{existing_content}{separator}{source_content}");
-            fs::write(&target_learnings, combined)?;
-        }
+    plan.execute(
+        match (args.dry_run, format) {
+            (false, _) => Execution::Apply,
+            (true, OutputFormat::Text) => Execution::Diff,
+            (true, OutputFormat::Json) => Execution::Print,
+        },
+        false,
+    )?;
+
+    if format == OutputFormat::Json {
+        let mut report = Report::new("learn", &args.target);
+        report.output_dir = Some(output_dir);
+        report.dry_run = args.dry_run;
+        report.files = plan
+            .files
+            .iter()
+            .map(|change| FileReport::from_change(change, &args.target, args.dry_run))
+            .collect();
+        report.learnings_added = plan.learnings_added;
+        return report.print();
+    }
+
+    if !recorded {
+        println!("📚 Learning already recorded: {}", learning.title);
+    } else if args.dry_run {
+        println!("🔍 Would record learning: {}", learning.title);
     } else {
-        // Will copy as new file
-        if verbose {
-            eprintln!(
-                "write {} ({} bytes)",
-                target_learnings.display(),
-                source_content.len()
-            );
+        println!(
+            "📚 Recorded learning: {} ({})",
+            learning.title,
+            output_dir.join("learnings.md").display()
+        );
+    }
+    Ok(())
+}
+
+/// Merge learnings from project directories into the learnings library
+fn run_harvest(args: &cli::HarvestArgs, format: OutputFormat) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    let Some(library) = args.library.clone().or(config.learnings_library) else {
+        anyhow::bail!(
+            "No learnings library configured; pass --library or set learnings-library \
+             in ~/.config/proact/config.toml"
+        );
+    };
+    let output = args.output.as_ref().unwrap_or(&library);
+
+    let harvest = learnings::plan_harvest(&library, &args.dirs, output)?;
+    for dir in &harvest.skipped {
+        eprintln!("# No learnings.md in {}, skipping", dir.display());
+    }
+    if format == OutputFormat::Text {
+        for (dir, title) in &harvest.added {
+            println!("📚 {}: {title}", dir.display());
         }
+    }
+
+    let plan = harvest.plan;
+    plan.execute(
+        match (args.diff, format) {
+            (false, _) => Execution::Apply,
+            (true, OutputFormat::Text) => Execution::Diff,
+            (true, OutputFormat::Json) => Execution::Print,
+        },
+        false,
+    )?;
 
-        if !dry_run {
-            fs::write(&target_learnings, source_content)?;
+    if format == OutputFormat::Json {
+        let mut report = Report::new("harvest", Path::new("."));
+        report.dry_run = args.diff;
+        report.files = plan
+            .files
+            .iter()
+            .map(|change| FileReport::from_change(change, Path::new("."), args.diff))
+            .collect();
+        report.learnings_added = plan.learnings_added;
+        return report.print();
+    }
+
+    let added = plan.learnings_added.len();
+    if args.diff {
+        println!("🔍 {added} new learning(s) found - library not written");
+    } else {
+        println!(
+            "📄 {}: {} ({added} new learning(s))",
+            plan.files[0].action.label(false),
+            output.display(),
+        );
+    }
+    Ok(())
+}
+
+/// Print the check-mode summary and exit non-zero if any file is stale
+fn report_check(files: &[FileChange]) -> Result<()> {
+    if files.iter().all(|change| !change.action.is_write()) {
+        println!("✅ Generated documentation is up to date");
+        for change in files {
+            let status = match change.action {
+                FileAction::Skipped => "Kept (differs from generated)",
+                _ => "Up to date",
+            };
+            println!("📄 {status}: {}", change.path.display());
         }
+        return Ok(());
     }
 
-    Ok(Some(target_exists))
+    println!("❌ Generated documentation is out of date");
+    for change in files {
+        println!(
+            "📄 {}: {}",
+            change.action.check_status(),
+            change.path.display()
+        );
+    }
+    println!("Run `proact update` to regenerate.");
+    std::process::exit(1);
 }
 
 /// Print version information with copyright and license
@@ -84,125 +164,176 @@ fn print_version_info() {
     );
 }
 
-/// Proact: A CLI that generates documentation for AI coding agents
-///
-/// This tool creates comprehensive documentation that instructs AI coding agents
-/// to follow best practices, apply continuous improvement feedback, and utilize
-/// tools like Playwright MCP for browser automation.
-fn main() -> Result<()> {
-    // Check for -V flag manually before parsing
-    if std::env::args().any(|arg| arg == "-V") {
-        print_version_info();
-        return Ok(());
+/// How `run_generate` treats the files it generates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Create or update every file
+    Init,
+    /// Refresh files that already exist without creating new ones
+    Update,
+    /// Report stale files without writing
+    Check,
+}
+
+impl Mode {
+    /// Subcommand name
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Init => "init",
+            Mode::Update => "update",
+            Mode::Check => "check",
+        }
     }
+}
 
-    let args = cli::Args::parse();
+/// Generate, refresh or check the documentation of a project
+fn run_generate(args: &cli::GenerateArgs, mode: Mode, format: OutputFormat) -> Result<()> {
+    let target = args
+        .target
+        .as_deref()
+        .expect("clap requires TARGET for init, update and check");
 
-    // Dry-run implies verbose
-    let verbose = args.verbose || args.dry_run;
+    // Diff implies dry-run, and dry-run implies verbose
+    let dry_run = args.dry_run || args.diff;
+    let verbose = args.verbose || dry_run;
 
     // Validate target path exists
-    if !args.target.exists() {
-        anyhow::bail!("Target path does not exist: {}", args.target.display());
+    if !target.exists() {
+        anyhow::bail!("Target path does not exist: {}", target.display());
     }
 
-    if !args.target.is_dir() {
-        anyhow::bail!("Target path must be a directory: {}", args.target.display());
+    if !target.is_dir() {
+        anyhow::bail!("Target path must be a directory: {}", target.display());
     }
 
-    // Resolve output directory relative to target
-    let output_dir = if args.output_dir.is_absolute() {
-        args.output_dir.clone()
-    } else {
-        args.target.join(&args.output_dir)
-    };
+    // Load .proact.toml and the user config; CLI flags override both
+    let config = Config::load(target)?;
+    let settings = Settings::resolve(target, &args.overrides(), &config)?;
+    let output_dir = &settings.output_dir;
+
+    let mut metadata = ProjectMetadata::extract(target)?;
+    metadata.apply_overrides(config.license.as_deref(), config.copyright_owner.as_deref());
 
     if verbose {
         eprintln!("Proact v{}", env!("CARGO_PKG_VERSION"));
-        eprintln!("Target project: {}", args.target.display());
+        eprintln!("Target project: {}", target.display());
         eprintln!("Output directory: {}", output_dir.display());
-        if args.dry_run {
+        if dry_run {
             eprintln!("Mode: DRY RUN (no files will be created)");
         }
+        if mode == Mode::Check {
+            eprintln!("Mode: CHECK (no files will be written)");
+        }
     }
 
-    // Generate the documentation
-    let doc_content = generator::generate_documentation(&args.target, verbose)?;
+    let plan = match mode {
+        Mode::Check => Plan::new(target, &settings, &metadata, args.verbose)?,
+        Mode::Init => Plan::new(target, &settings, &metadata, verbose)?,
+        Mode::Update => {
+            if !output_dir.join("ai_agent_instructions.md").exists() {
+                anyhow::bail!(
+                    "{} has not been initialized; run `proact init {}` first",
+                    target.display(),
+                    target.display()
+                );
+            }
+            // Write only files that already exist
+            Plan::new(target, &settings, &metadata, verbose)?.existing_files_only()
+        }
+    };
 
-    // Determine output file path
-    let output_file = output_dir.join("ai_agent_instructions.md");
+    // Every mode executes the same operations; only real runs write them
+    let execution = if mode == Mode::Check || (args.diff && format == OutputFormat::Json) {
+        // JSON reports carry the diffs themselves
+        Execution::Print
+    } else if args.diff {
+        Execution::Diff
+    } else if dry_run {
+        Execution::Print
+    } else {
+        Execution::Apply
+    };
+    let describe = if mode == Mode::Check {
+        args.verbose
+    } else {
+        verbose
+    };
+    plan.execute(execution, describe)?;
 
-    // Create output directory if it doesn't exist
-    if !output_dir.exists() {
-        if verbose {
-            eprintln!("mkdir -p {}", output_dir.display());
-        }
-        if !args.dry_run {
-            std::fs::create_dir_all(&output_dir)?;
-        }
-    } else if verbose {
-        eprintln!("# Directory already exists: {}", output_dir.display());
-    }
+    let up_to_date = plan.is_up_to_date();
+    let Plan {
+        files,
+        learnings_added,
+        warnings,
+        ..
+    } = plan;
 
-    // Write the documentation
-    if verbose {
-        eprintln!(
-            "write {} ({} bytes)",
-            output_file.display(),
-            doc_content.len()
-        );
-    }
+    let project_types = project_types(target, &settings.detectors);
 
-    if !args.dry_run {
-        std::fs::write(&output_file, doc_content)?;
+    // Record what proact owns so `proact clean` can remove exactly that
+    if !dry_run && mode != Mode::Check {
+        let mut manifest = Manifest::load(target)?.unwrap_or_default();
+        manifest.proact = BuildInfo::current();
+        manifest.command = mode.name().to_string();
+        manifest.options = manifest::Options::new(target, &settings);
+        manifest.project_types = project_types.clone();
+        manifest.record(target, &output_dir.join("learnings.md"), &files);
+        manifest.save(target, verbose)?;
     }
 
-    // Copy template files (process.md, tools.md)
-    generator::copy_templates(&output_dir, verbose, args.dry_run)?;
-
-    // Generate COPYRIGHT and LICENSE files
-    generator::generate_legal_files(&args.target, &output_dir, verbose, args.dry_run)?;
+    if format == OutputFormat::Json {
+        let mut report = Report::new(mode.name(), target);
+        report.output_dir = Some(output_dir.clone());
+        report.dry_run = dry_run || mode == Mode::Check;
+        report.project_types = project_types;
+        report.warnings = warnings;
+        report.metadata = Some(metadata);
+        report.files = files
+            .iter()
+            .map(|change| FileReport::from_change(change, target, args.diff))
+            .collect();
+        report.learnings_added = learnings_added;
+        if mode == Mode::Check {
+            report.up_to_date = Some(up_to_date);
+        }
+        report.print()?;
+        if !up_to_date && mode == Mode::Check {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
-    // Copy or append learnings.md
-    let learnings_action = handle_learnings_file(&output_dir, args.dry_run, verbose)?;
+    if mode == Mode::Check {
+        return report_check(&files);
+    }
 
-    if !args.dry_run {
+    if !dry_run {
         println!("✅ AI agent documentation generated successfully!");
-        println!("📄 Created: {}", output_file.display());
-        println!("📄 Created: {}", output_dir.join("process.md").display());
-        println!("📄 Created: {}", output_dir.join("tools.md").display());
-        println!("📄 Created: {}", args.target.join("COPYRIGHT").display());
-        println!("📄 Created: {}", args.target.join("LICENSE").display());
-
-        if let Some(appended) = learnings_action {
-            let learnings_file = output_dir.join("learnings.md");
-            if appended {
-                println!("📄 Appended to: {}", learnings_file.display());
-            } else {
-                println!("📄 Created: {}", learnings_file.display());
-            }
-        }
     } else {
         println!("🔍 DRY RUN completed - no files were created");
-        println!("📄 Would create: {}", output_file.display());
-        println!(
-            "📄 Would create: {}",
-            output_dir.join("process.md").display()
-        );
-        println!("📄 Would create: {}", output_dir.join("tools.md").display());
+    }
+    for change in &files {
         println!(
-            "📄 Would create: {}",
-            args.target.join("COPYRIGHT").display()
+            "📄 {}: {}",
+            change.action.label(dry_run),
+            change.path.display()
         );
-        println!("📄 Would create: {}", args.target.join("LICENSE").display());
-        if let Some(appended) = learnings_action {
-            let learnings_file = output_dir.join("learnings.md");
-            if appended {
-                println!("📄 Would append to: {}", learnings_file.display());
-            } else {
-                println!("📄 Would create: {}", learnings_file.display());
-            }
-        }
+    }
+    let count = |actions: &[FileAction]| {
+        files
+            .iter()
+            .filter(|change| actions.contains(&change.action))
+            .count()
+    };
+    println!(
+        "{} created, {} updated, {} unchanged, {} skipped",
+        count(&[FileAction::Created]),
+        count(&[FileAction::Appended, FileAction::Replaced]),
+        count(&[FileAction::Unchanged]),
+        count(&[FileAction::Skipped])
+    );
+    for title in &learnings_added {
+        println!("📚 New learning: {title}");
     }
 
     if verbose {
@@ -211,7 +342,7 @@ fn main() -> Result<()> {
         eprintln!("  • Development process guidelines (process.md)");
         eprintln!("  • Development tools reference (tools.md)");
         eprintln!("  • Copyright notice (COPYRIGHT)");
-        eprintln!("  • MIT License file (LICENSE)");
+        eprintln!("  • License file(s) matching the declared SPDX license");
         eprintln!("  • Continuous improvement practices");
         eprintln!("  • Playwright MCP setup instructions");
         eprintln!("  • Quality standards and testing requirements");
@@ -220,3 +351,102 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Print a single section of the agent instructions
+fn run_show(args: &cli::ShowArgs, format: OutputFormat) -> Result<()> {
+    let detectors = Config::load(&args.target)?.detectors()?;
+    let content = render_section(&args.target, args.section, &detectors, false)?;
+
+    if format == OutputFormat::Json {
+        let mut report = Report::new("show", &args.target);
+        report.section = Some(args.section);
+        report.content = content;
+        return report.print();
+    }
+
+    match content {
+        Some(content) => print!("{content}"),
+        None => eprintln!("# No project-specific notes for {}", args.target.display()),
+    }
+    Ok(())
+}
+
+/// Remove proact-managed content from a project
+fn run_clean(args: &cli::CleanArgs, format: OutputFormat) -> Result<()> {
+    if !args.target.is_dir() {
+        anyhow::bail!("Target path must be a directory: {}", args.target.display());
+    }
+
+    let config = Config::load(&args.target)?;
+    let output_dir = resolve_output_dir(&args.target, args.output_dir.as_deref(), &config);
+    let cleaned = clean::clean(
+        &args.target,
+        &output_dir,
+        &config.templates,
+        args.dry_run,
+        args.dry_run,
+    )?;
+
+    if format == OutputFormat::Json {
+        let mut report = Report::new("clean", &args.target);
+        report.output_dir = Some(output_dir);
+        report.dry_run = args.dry_run;
+        report.files = cleaned.iter().map(FileReport::from_cleaned).collect();
+        report.warnings = cleaned
+            .iter()
+            .filter(|file| file.action == clean::CleanAction::Kept)
+            .map(|file| {
+                format!(
+                    "{} was edited since proact created it; kept it",
+                    file.path.display()
+                )
+            })
+            .collect();
+        return report.print();
+    }
+
+    if cleaned.is_empty() {
+        println!("✅ No proact-managed content found");
+    } else if args.dry_run {
+        println!("🔍 DRY RUN completed - no files were changed");
+    } else {
+        println!("✅ Proact-managed content removed");
+    }
+    for file in &cleaned {
+        println!(
+            "📄 {}: {}",
+            file.action.label(args.dry_run),
+            file.path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Proact: A CLI that generates documentation for AI coding agents
+///
+/// This tool creates comprehensive documentation that instructs AI coding agents
+/// to follow best practices, apply continuous improvement feedback, and utilize
+/// tools like Playwright MCP for browser automation.
+fn main() -> Result<()> {
+    // Check for -V flag manually before parsing
+    if std::env::args().any(|arg| arg == "-V") {
+        print_version_info();
+        return Ok(());
+    }
+
+    let args = cli::Args::parse();
+    let format = args.format;
+
+    match &args.command {
+        Some(cli::Command::Init(generate)) => run_generate(generate, Mode::Init, format),
+        Some(cli::Command::Update(generate)) => run_generate(generate, Mode::Update, format),
+        Some(cli::Command::Check(generate)) => run_generate(generate, Mode::Check, format),
+        Some(cli::Command::Show(show)) => run_show(show, format),
+        Some(cli::Command::Clean(clean)) => run_clean(clean, format),
+        Some(cli::Command::Learn(learn)) => run_learn(learn, format),
+        Some(cli::Command::Harvest(harvest)) => run_harvest(harvest, format),
+        // The bare invocation is an alias for `init` (or `check` with --check)
+        None if args.generate.check => run_generate(&args.generate, Mode::Check, format),
+        None => run_generate(&args.generate, Mode::Init, format),
+    }
+}
@@ -0,0 +1,532 @@
+//! Managed sections in generated files
+//!
+//! Proact writes its content between begin/end markers so that regeneration
+//! replaces only its own region and leaves user-authored content outside the
+//! markers untouched. The begin marker records the proact version and a hash
+//! of the managed content.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::files::read_existing;
+
+/// Prefix of the marker line that opens a managed section
+pub const BEGIN_MARKER: &str = "<!-- proact:begin";
+
+/// Marker line that closes a managed section
+pub const END_MARKER: &str = "<!-- proact:end -->";
+
+/// Heading that opened the content appended by proact before managed
+/// sections existed; everything from it onward was generated
+const LEGACY_HEADING: &str = "**The following content was added by [Proact]";
+
+/// What happened (or would happen) to a generated file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileAction {
+    /// The file did not exist and was created
+    Created,
+    /// The file existed without markers; the managed section was appended
+    Appended,
+    /// The existing managed section (or whole file) was replaced
+    Replaced,
+    /// The existing managed section (or whole file) already matched
+    Unchanged,
+    /// The existing file differs and was kept instead of being overwritten
+    Skipped,
+}
+
+impl FileAction {
+    /// Human-readable label used in the run summary
+    pub fn label(self, dry_run: bool) -> &'static str {
+        match (self, dry_run) {
+            (Self::Created, false) => "Created",
+            (Self::Created, true) => "Would create",
+            (Self::Appended, false) => "Appended to",
+            (Self::Appended, true) => "Would append to",
+            (Self::Replaced, false) => "Updated",
+            (Self::Replaced, true) => "Would update",
+            (Self::Unchanged, _) => "Unchanged",
+            (Self::Skipped, _) => "Skipped (existing file differs)",
+        }
+    }
+
+    /// Short status used when reporting stale files in check mode
+    pub fn check_status(self) -> &'static str {
+        match self {
+            Self::Created => "missing",
+            Self::Appended => "missing managed section",
+            Self::Replaced => "stale",
+            Self::Unchanged => "up to date",
+            Self::Skipped => "kept (differs from generated)",
+        }
+    }
+
+    /// Whether the file is (or would be) written
+    pub fn is_write(self) -> bool {
+        matches!(self, Self::Created | Self::Appended | Self::Replaced)
+    }
+}
+
+/// How to handle an existing file whose content differs from what proact
+/// would generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Overwrite {
+    /// Keep the existing file and report it as skipped
+    #[default]
+    Refuse,
+    /// Replace the existing file
+    Force,
+    /// Copy the existing file to a `.bak` file, then replace it
+    Backup,
+}
+
+/// A generated file with its content before and after the change
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub action: FileAction,
+    /// Content on disk before the change, if the file existed
+    pub before: Option<String>,
+    /// Full file content after the change
+    pub after: String,
+}
+
+/// A managed section located inside a file
+struct Section<'a> {
+    /// Byte offset of the begin marker line
+    start: usize,
+    /// Byte offset just past the end marker line
+    end: usize,
+    /// Hash recorded in the begin marker, if any
+    hash: Option<&'a str>,
+    /// Content between the markers
+    body: &'a str,
+}
+
+/// Compute a stable 64-bit FNV-1a hash of content as 16 hex digits
+pub fn content_hash(content: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in content.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// Wrap content in begin/end markers
+pub fn wrap(content: &str) -> String {
+    let body = ensure_trailing_newline(content);
+    format!(
+        "{BEGIN_MARKER} version={} hash={} -->\n{body}{END_MARKER}\n",
+        env!("CARGO_PKG_VERSION"),
+        content_hash(&body)
+    )
+}
+
+/// Merge managed content into the existing file content
+///
+/// Returns the new file content and the action it represents. Content outside
+/// the markers is preserved byte for byte.
+pub fn merge(existing: Option<&str>, content: &str) -> (String, FileAction) {
+    let block = wrap(content);
+
+    let Some(existing) = existing else {
+        return (block, FileAction::Created);
+    };
+
+    match find_section(existing) {
+        Some(section) => {
+            if section.body == ensure_trailing_newline(content)
+                && section.hash == Some(content_hash(section.body).as_str())
+            {
+                return (existing.to_string(), FileAction::Unchanged);
+            }
+            let merged = format!(
+                "{}{block}{}",
+                &existing[..section.start],
+                &existing[section.end..]
+            );
+            (merged, FileAction::Replaced)
+        }
+        None => {
+            if let Some(user_content) = strip_legacy(existing) {
+                let merged = if user_content.is_empty() {
+                    block
+                } else {
+                    format!("{user_content}\n\n{block}")
+                };
+                return (merged, FileAction::Replaced);
+            }
+            let separator = if existing.is_empty() || existing.ends_with("\n\n") {
+                ""
+            } else if existing.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            (
+                format!("{existing}{separator}{block}"),
+                FileAction::Appended,
+            )
+        }
+    }
+}
+
+/// Whether the managed section in the content was edited by hand since it
+/// was last written (its body no longer matches the recorded hash)
+pub fn was_edited(existing: &str) -> bool {
+    find_section(existing).is_some_and(|section| {
+        section
+            .hash
+            .is_some_and(|hash| hash != content_hash(section.body))
+    })
+}
+
+/// Remove the managed section, markers included, keeping everything else
+///
+/// Returns None when the content has no managed section. The blank line
+/// that separated an appended section from the user's content is removed.
+pub fn strip_section(existing: &str) -> Option<String> {
+    let section = find_section(existing)?;
+    let before = &existing[..section.start];
+    let after = &existing[section.end..];
+
+    if after.is_empty() {
+        let before = before.trim_end();
+        return Some(if before.is_empty() {
+            String::new()
+        } else {
+            format!("{before}\n")
+        });
+    }
+    Some(format!("{before}{after}"))
+}
+
+/// Whether `path` is a backup of `original` planned by `plan_protected`
+pub fn is_backup_of(path: &Path, original: &Path) -> bool {
+    let mut prefix = original.as_os_str().to_owned();
+    prefix.push(".bak");
+    path.as_os_str()
+        .to_str()
+        .zip(prefix.to_str())
+        .is_some_and(|(path, prefix)| path.starts_with(prefix))
+}
+
+/// Remove the marker lines of the managed section, keeping its content
+pub fn unwrap_section(existing: &str) -> String {
+    match find_section(existing) {
+        Some(section) => format!(
+            "{}{}{}",
+            &existing[..section.start],
+            section.body,
+            &existing[section.end..]
+        ),
+        None => existing.to_string(),
+    }
+}
+
+/// Plan managed content for a file: create it or update its managed section
+/// in place
+pub fn plan_managed(path: &Path, content: &str) -> Result<FileChange> {
+    let existing = read_existing(path)?;
+    let (merged, action) = merge(existing.as_deref(), content);
+
+    Ok(FileChange {
+        path: path.to_path_buf(),
+        action,
+        before: existing,
+        after: merged,
+    })
+}
+
+/// Plan a file that proact generates in full (no managed section)
+pub fn plan_file(path: &Path, content: &str) -> Result<FileChange> {
+    let existing = read_existing(path)?;
+
+    let action = match existing.as_deref() {
+        None => FileAction::Created,
+        Some(existing) if existing == content => FileAction::Unchanged,
+        Some(_) => FileAction::Replaced,
+    };
+
+    Ok(FileChange {
+        path: path.to_path_buf(),
+        action,
+        before: existing,
+        after: content.to_string(),
+    })
+}
+
+/// Plan a file that must never be overwritten silently (LICENSE, COPYRIGHT)
+///
+/// An existing file is compared with the generated content, ignoring
+/// trailing whitespace. If it differs it is kept unless `overwrite` says
+/// otherwise. Returns the change to the file, preceded by the backup file
+/// when one is written.
+pub fn plan_protected(path: &Path, content: &str, overwrite: Overwrite) -> Result<Vec<FileChange>> {
+    let Some(existing) = read_existing(path)? else {
+        return Ok(vec![plan_file(path, content)?]);
+    };
+
+    if normalize(&existing) == normalize(content) {
+        return Ok(vec![FileChange {
+            path: path.to_path_buf(),
+            action: FileAction::Unchanged,
+            before: Some(existing.clone()),
+            after: existing,
+        }]);
+    }
+
+    match overwrite {
+        Overwrite::Refuse => Ok(vec![FileChange {
+            path: path.to_path_buf(),
+            action: FileAction::Skipped,
+            before: Some(existing.clone()),
+            after: existing,
+        }]),
+        Overwrite::Force => Ok(vec![plan_file(path, content)?]),
+        Overwrite::Backup => {
+            let backup = plan_file(&backup_path(path), &existing)?;
+            let change = plan_file(path, content)?;
+            Ok(vec![backup, change])
+        }
+    }
+}
+
+/// First unused backup path: `LICENSE.bak`, `LICENSE.bak.1`, ...
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    let base = PathBuf::from(name);
+
+    let mut candidate = base.clone();
+    let mut n = 1;
+    while candidate.exists() {
+        let mut name = base.as_os_str().to_owned();
+        name.push(format!(".{n}"));
+        candidate = PathBuf::from(name);
+        n += 1;
+    }
+    candidate
+}
+
+/// Content with trailing whitespace removed from every line and the end
+fn normalize(content: &str) -> String {
+    content
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+/// Locate the first managed section in the content
+fn find_section(content: &str) -> Option<Section<'_>> {
+    let start = line_start_of(content, BEGIN_MARKER)?;
+    let begin_line_end = content[start..].find('\n').map(|i| start + i + 1)?;
+    let begin_line = &content[start..begin_line_end];
+
+    let end_offset = line_start_of(&content[begin_line_end..], END_MARKER)?;
+    let end_start = begin_line_end + end_offset;
+    let end = content[end_start..]
+        .find('\n')
+        .map_or(content.len(), |i| end_start + i + 1);
+
+    let hash = begin_line
+        .split_whitespace()
+        .find_map(|token| token.strip_prefix("hash="));
+
+    Some(Section {
+        start,
+        end,
+        hash,
+        body: &content[begin_line_end..end_start],
+    })
+}
+
+/// Find the byte offset of the first line that starts with the marker
+fn line_start_of(content: &str, marker: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if line.starts_with(marker) {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// The user's content preceding the first legacy appended block, without
+/// its `---` separator, or None if the content has no legacy block
+fn strip_legacy(content: &str) -> Option<&str> {
+    let start = line_start_of(content, LEGACY_HEADING)?;
+    let before = content[..start].trim_end();
+    Some(before.strip_suffix("---").unwrap_or(before).trim_end())
+}
+
+fn ensure_trailing_newline(content: &str) -> String {
+    if content.ends_with('\n') {
+        content.to_string()
+    } else {
+        format!("{content}\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::setup_test_dir;
+    use std::fs;
+
+    #[test]
+    fn test_merge_creates_new_file() {
+        let (merged, action) = merge(None, "hello");
+        assert_eq!(action, FileAction::Created);
+        assert!(merged.starts_with(BEGIN_MARKER));
+        assert!(merged.contains("hello\n"));
+        assert!(merged.ends_with(&format!("{END_MARKER}\n")));
+    }
+
+    #[test]
+    fn test_merge_appends_to_unmanaged_file() {
+        let (merged, action) = merge(Some("# Notes\n"), "hello");
+        assert_eq!(action, FileAction::Appended);
+        assert!(merged.starts_with("# Notes\n\n"));
+        assert!(merged.contains("hello\n"));
+    }
+
+    #[test]
+    fn test_merge_replaces_legacy_appended_blocks() {
+        let legacy = "\n\n---\n\n**The following content was added by \
+                      [Proact](https://github.com/softwarewrighter/proact) on 2025-01-01**\n\n\
+                      # Old process\n";
+        let existing = format!("# Notes\n{legacy}{legacy}");
+
+        let (merged, action) = merge(Some(&existing), "new");
+        assert_eq!(action, FileAction::Replaced);
+        assert!(merged.starts_with("# Notes\n\n"));
+        assert!(merged.ends_with("new\n<!-- proact:end -->\n"));
+        assert!(!merged.contains("The following content"));
+        assert!(!merged.contains("---"));
+        assert_eq!(merged.matches(BEGIN_MARKER).count(), 1);
+
+        let (again, action) = merge(Some(&merged), "new");
+        assert_eq!(action, FileAction::Unchanged);
+        assert_eq!(again, merged);
+    }
+
+    #[test]
+    fn test_merge_replaces_only_managed_section() {
+        let (first, _) = merge(Some("# Top\n"), "old");
+        let existing = format!("{first}\n## User section\n");
+
+        let (merged, action) = merge(Some(&existing), "new");
+        assert_eq!(action, FileAction::Replaced);
+        assert!(merged.starts_with("# Top\n"));
+        assert!(merged.ends_with("\n## User section\n"));
+        assert!(merged.contains("new\n"));
+        assert!(!merged.contains("old\n"));
+        assert_eq!(merged.matches(BEGIN_MARKER).count(), 1);
+    }
+
+    #[test]
+    fn test_merge_is_idempotent() {
+        let (first, _) = merge(None, "content");
+        let (second, action) = merge(Some(&first), "content");
+        assert_eq!(action, FileAction::Unchanged);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_strip_section_restores_user_content() {
+        let (merged, _) = merge(Some("# Notes\n"), "generated");
+        assert_eq!(strip_section(&merged).as_deref(), Some("# Notes\n"));
+
+        let (created, _) = merge(None, "generated");
+        assert_eq!(strip_section(&created).as_deref(), Some(""));
+
+        let middle = format!("# Top\n{created}## Bottom\n");
+        assert_eq!(
+            strip_section(&middle).as_deref(),
+            Some("# Top\n## Bottom\n")
+        );
+
+        assert!(strip_section("# No markers\n").is_none());
+    }
+
+    #[test]
+    fn test_is_backup_of() {
+        assert!(is_backup_of(
+            Path::new("p/LICENSE.bak"),
+            Path::new("p/LICENSE")
+        ));
+        assert!(is_backup_of(
+            Path::new("p/LICENSE.bak.2"),
+            Path::new("p/LICENSE")
+        ));
+        assert!(!is_backup_of(
+            Path::new("p/COPYRIGHT.bak"),
+            Path::new("p/LICENSE")
+        ));
+    }
+
+    #[test]
+    fn test_was_edited_detects_hand_edits() {
+        let (written, _) = merge(None, "content");
+        assert!(!was_edited(&written));
+        let edited = written.replace("content", "changed");
+        assert!(was_edited(&edited));
+    }
+
+    #[test]
+    fn test_plan_protected_refuses_different_file() {
+        let dir = setup_test_dir("managed", "refuse");
+        let path = dir.join("LICENSE");
+        fs::write(&path, "Custom license\n").unwrap();
+
+        let changes = plan_protected(&path, "MIT License\n", Overwrite::Refuse).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].action, FileAction::Skipped);
+        assert_eq!(changes[0].after, "Custom license\n");
+    }
+
+    #[test]
+    fn test_plan_protected_ignores_trailing_whitespace() {
+        let dir = setup_test_dir("managed", "whitespace");
+        let path = dir.join("COPYRIGHT");
+        fs::write(&path, "Copyright (c) 2025 Someone  \n\n").unwrap();
+
+        let changes =
+            plan_protected(&path, "Copyright (c) 2025 Someone", Overwrite::Refuse).unwrap();
+        assert_eq!(changes[0].action, FileAction::Unchanged);
+    }
+
+    #[test]
+    fn test_plan_protected_backup_then_replace() {
+        let dir = setup_test_dir("managed", "backup");
+        let path = dir.join("LICENSE");
+        fs::write(&path, "Custom license\n").unwrap();
+        fs::write(dir.join("LICENSE.bak"), "Older backup\n").unwrap();
+
+        let changes = plan_protected(&path, "MIT License\n", Overwrite::Backup).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path, dir.join("LICENSE.bak.1"));
+        assert_eq!(changes[0].action, FileAction::Created);
+        assert_eq!(changes[0].after, "Custom license\n");
+        assert_eq!(changes[1].action, FileAction::Replaced);
+        assert_eq!(changes[1].after, "MIT License\n");
+        // Planning writes nothing
+        assert!(!dir.join("LICENSE.bak.1").exists());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Custom license\n");
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_ne!(content_hash("a"), content_hash("b"));
+    }
+}
@@ -0,0 +1,464 @@
+//! Record of what proact generated
+//!
+//! Every run that writes files records in `.proact/manifest.json` the proact
+//! build, the options and detected project types, and each file it wrote
+//! with the action taken, its size and a hash of its content. The manifest
+//! also tracks which part of each file proact owns: files it created, files
+//! carrying a managed section, and learnings or MCP servers it merged into
+//! existing files. `proact clean` uses it to remove exactly that content.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::agents::Agent;
+use crate::config::Settings;
+use crate::generator::DocSection;
+use crate::learnings;
+use crate::managed::{self, FileAction, FileChange, Overwrite};
+use crate::mcp::{self, McpServer};
+
+/// Location of the manifest relative to the project root
+pub const MANIFEST_PATH: &str = ".proact/manifest.json";
+
+/// What part of a file proact owns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ownership {
+    /// Proact created the whole file and it still holds what proact wrote
+    Created,
+    /// Proact owns the managed section only
+    Managed,
+    /// Proact merged `entries` (learnings or MCP servers) into the file
+    Merged,
+}
+
+/// A file written by the last run that touched it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRecord {
+    /// Path relative to the project root, with `/` separators
+    pub path: String,
+    pub action: FileAction,
+    /// Size of the file after the run
+    pub bytes: usize,
+    /// Hash of the file content after the run
+    pub hash: String,
+    /// None when the file belongs to the project (e.g. a replaced LICENSE)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ownership: Option<Ownership>,
+    /// Learning IDs or MCP server names added by proact
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<String>,
+}
+
+/// The proact build that wrote the manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BuildInfo {
+    pub version: String,
+    pub git_hash: String,
+    pub build_timestamp: String,
+}
+
+impl BuildInfo {
+    /// The running build, as embedded by build.rs
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: env!("GIT_SHORT_HASH").to_string(),
+            build_timestamp: env!("BUILD_TIMESTAMP").to_string(),
+        }
+    }
+}
+
+/// Options the run was made with, after merging CLI flags and configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Options {
+    /// Output directory relative to the project root (as given when it is
+    /// outside the project)
+    pub output_dir: String,
+    pub sections: Vec<DocSection>,
+    pub templates: Vec<PathBuf>,
+    pub agents: Vec<Agent>,
+    pub mcp_servers: Vec<McpServer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub learnings_library: Option<PathBuf>,
+    pub overwrite: Overwrite,
+    /// Whether agent instructions were written into workspace packages
+    #[serde(default)]
+    pub per_package: bool,
+    /// Project detectors that ran, in order
+    #[serde(default)]
+    pub detectors: Vec<String>,
+}
+
+impl Options {
+    /// Options of a run on `target_path`
+    pub fn new(target_path: &Path, settings: &Settings) -> Self {
+        Self {
+            output_dir: if settings.output_dir == target_path {
+                ".".to_string()
+            } else {
+                relative_path(target_path, &settings.output_dir)
+                    .unwrap_or_else(|| settings.output_dir.display().to_string())
+            },
+            sections: settings.sections.clone(),
+            templates: settings.templates.clone(),
+            agents: settings.agents.clone(),
+            mcp_servers: settings.mcp_servers.clone(),
+            learnings_library: settings.learnings_library.clone(),
+            overwrite: settings.overwrite,
+            per_package: settings.per_package,
+            detectors: settings
+                .detectors
+                .enabled()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+/// Everything proact generated in a project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Manifest {
+    #[serde(default)]
+    pub proact: BuildInfo,
+    /// Subcommand of the last run (`init` or `update`)
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub options: Options,
+    #[serde(default)]
+    pub project_types: Vec<String>,
+    pub files: Vec<FileRecord>,
+}
+
+impl Manifest {
+    /// Load the manifest of a project, if it has one
+    pub fn load(target_path: &Path) -> Result<Option<Self>> {
+        let path = target_path.join(MANIFEST_PATH);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        let manifest = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(manifest))
+    }
+
+    /// Write the manifest into the project
+    pub fn save(&self, target_path: &Path, verbose: bool) -> Result<()> {
+        let path = target_path.join(MANIFEST_PATH);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if verbose {
+            eprintln!("write {}", path.display());
+        }
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        fs::write(&path, content)?;
+        Ok(())
+    }
+
+    /// Look up the record of a file
+    pub fn get(&self, path: &str) -> Option<&FileRecord> {
+        self.files.iter().find(|record| record.path == path)
+    }
+
+    /// Record the changes of a run
+    ///
+    /// Ownership established by earlier runs is kept, so a file proact
+    /// created stays owned while it holds what proact wrote. Backups and
+    /// files proact replaced or skipped are never owned. Files outside the
+    /// project are not recorded; the plan warns about them.
+    pub fn record(&mut self, target_path: &Path, learnings_path: &Path, files: &[FileChange]) {
+        for change in files {
+            let Some(path) = relative_path(target_path, &change.path) else {
+                continue;
+            };
+            let is_backup = files
+                .iter()
+                .any(|other| managed::is_backup_of(&change.path, &other.path));
+            let entries = if change.path == learnings_path {
+                Some(Entries::Learnings)
+            } else if mcp::is_config_file(&path) {
+                Some(Entries::McpServers)
+            } else {
+                None
+            };
+
+            let (ownership, entries) = if is_backup {
+                (None, Vec::new())
+            } else {
+                self.ownership(&path, change, entries)
+            };
+            let record = FileRecord {
+                path,
+                action: change.action,
+                bytes: change.after.len(),
+                hash: managed::content_hash(&change.after),
+                ownership,
+                entries,
+            };
+
+            match self
+                .files
+                .iter_mut()
+                .find(|existing| existing.path == record.path)
+            {
+                Some(existing) => *existing = record,
+                None => self.files.push(record),
+            }
+        }
+    }
+
+    /// What part of a changed file proact owns, with the entries it added
+    fn ownership(
+        &self,
+        path: &str,
+        change: &FileChange,
+        entries: Option<Entries>,
+    ) -> (Option<Ownership>, Vec<String>) {
+        if managed::strip_section(&change.after).is_some() {
+            return (Some(Ownership::Managed), Vec::new());
+        }
+        let Some(before) = change.before.as_deref() else {
+            return (Some(Ownership::Created), Vec::new());
+        };
+
+        let previous = self.get(path);
+        if let Some(previous) = previous
+            && previous.ownership == Some(Ownership::Created)
+        {
+            // Still exactly what proact wrote: the new content is owned too
+            let unedited = previous.hash == managed::content_hash(before);
+            return (unedited.then_some(Ownership::Created), Vec::new());
+        }
+
+        let Some(kind) = entries else {
+            return (None, Vec::new());
+        };
+        let known = kind.names(path, before);
+        let mut added = previous
+            .filter(|previous| previous.ownership == Some(Ownership::Merged))
+            .map(|previous| previous.entries.clone())
+            .unwrap_or_default();
+        for name in kind.names(path, &change.after) {
+            if !known.contains(&name) && !added.contains(&name) {
+                added.push(name);
+            }
+        }
+        if added.is_empty() {
+            (None, added)
+        } else {
+            (Some(Ownership::Merged), added)
+        }
+    }
+}
+
+/// Kind of entries proact merges into existing files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entries {
+    Learnings,
+    McpServers,
+}
+
+impl Entries {
+    /// Names of the entries in a file
+    fn names(self, path: &str, content: &str) -> Vec<String> {
+        match self {
+            Entries::Learnings => learnings::parse(content)
+                .into_iter()
+                .map(|learning| learning.id)
+                .collect(),
+            Entries::McpServers => mcp::server_names(path, content),
+        }
+    }
+}
+
+/// Path of `path` relative to the project root, with `/` separators
+///
+/// Returns None for the root itself and for paths outside the project,
+/// including those that leave it through `..`.
+pub fn relative_path(target_path: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(target_path).ok()?;
+    let mut parts = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(path: &str, action: FileAction, before: Option<&str>, after: &str) -> FileChange {
+        FileChange {
+            path: PathBuf::from("project").join(path),
+            action,
+            before: before.map(str::to_string),
+            after: after.to_string(),
+        }
+    }
+
+    fn record(manifest: &mut Manifest, files: &[FileChange]) {
+        let learnings = Path::new("project").join("docs").join("learnings.md");
+        manifest.record(Path::new("project"), &learnings, files);
+    }
+
+    fn ownership(manifest: &Manifest) -> Vec<Option<Ownership>> {
+        manifest
+            .files
+            .iter()
+            .map(|record| record.ownership)
+            .collect()
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let target = Path::new("project");
+        assert_eq!(
+            relative_path(target, &target.join("./docs/process.md")).as_deref(),
+            Some("docs/process.md")
+        );
+        assert_eq!(relative_path(target, target), None);
+        assert_eq!(
+            relative_path(target, &target.join("../docs/process.md")),
+            None
+        );
+        assert_eq!(
+            relative_path(target, Path::new("/tmp/docs/process.md")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_record_files_and_ownership() {
+        let (managed, _) = managed::merge(Some("# Notes\n"), "generated");
+        let mut manifest = Manifest::default();
+        record(
+            &mut manifest,
+            &[
+                change("LICENSE", FileAction::Created, None, "MIT"),
+                change(
+                    "CLAUDE.md",
+                    FileAction::Appended,
+                    Some("# Notes\n"),
+                    &managed,
+                ),
+                change("COPYRIGHT", FileAction::Skipped, Some("ours"), "ours"),
+                change(
+                    ".mcp.json",
+                    FileAction::Replaced,
+                    Some(r#"{"mcpServers": {"github": {}}}"#),
+                    r#"{"mcpServers": {"github": {}, "playwright": {}}}"#,
+                ),
+            ],
+        );
+
+        let paths: Vec<&str> = manifest.files.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["LICENSE", "CLAUDE.md", "COPYRIGHT", ".mcp.json"]);
+        assert_eq!(
+            ownership(&manifest),
+            [
+                Some(Ownership::Created),
+                Some(Ownership::Managed),
+                None,
+                Some(Ownership::Merged)
+            ]
+        );
+        assert_eq!(manifest.files[0].action, FileAction::Created);
+        assert_eq!(manifest.files[0].bytes, 3);
+        assert_eq!(manifest.files[0].hash, managed::content_hash("MIT"));
+        assert_eq!(manifest.files[2].action, FileAction::Skipped);
+        assert_eq!(manifest.files[3].entries, ["playwright"]);
+    }
+
+    #[test]
+    fn test_record_keeps_ownership_across_runs() {
+        let mut manifest = Manifest::default();
+        record(
+            &mut manifest,
+            &[change("LICENSE", FileAction::Created, None, "MIT")],
+        );
+        record(
+            &mut manifest,
+            &[
+                change("LICENSE.bak", FileAction::Created, None, "MIT"),
+                change("LICENSE", FileAction::Replaced, Some("MIT"), "MIT 2026"),
+            ],
+        );
+        assert_eq!(ownership(&manifest), [Some(Ownership::Created), None]);
+        assert_eq!(manifest.files[0].hash, managed::content_hash("MIT 2026"));
+
+        // Edited by hand: the file now belongs to the project
+        record(
+            &mut manifest,
+            &[change(
+                "LICENSE",
+                FileAction::Skipped,
+                Some("edited"),
+                "edited",
+            )],
+        );
+        assert_eq!(manifest.files[0].ownership, None);
+    }
+
+    #[test]
+    fn test_record_merged_learnings() {
+        let existing = "### 1. Ours\n\nLocal.\n";
+        let merged = "### 1. Ours\n\nLocal.\n\n### 2. Unused Imports\n\nSeed.\n";
+        let mut manifest = Manifest::default();
+        record(
+            &mut manifest,
+            &[change(
+                "docs/learnings.md",
+                FileAction::Replaced,
+                Some(existing),
+                merged,
+            )],
+        );
+        assert_eq!(manifest.files[0].ownership, Some(Ownership::Merged));
+        assert_eq!(manifest.files[0].entries, ["unused-imports"]);
+
+        record(
+            &mut manifest,
+            &[change(
+                "docs/learnings.md",
+                FileAction::Unchanged,
+                Some(merged),
+                merged,
+            )],
+        );
+        assert_eq!(manifest.files[0].entries, ["unused-imports"]);
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let mut manifest = Manifest {
+            proact: BuildInfo::current(),
+            command: "init".to_string(),
+            project_types: vec!["rust".to_string()],
+            ..Manifest::default()
+        };
+        record(
+            &mut manifest,
+            &[change("LICENSE", FileAction::Created, None, "MIT")],
+        );
+
+        let json = serde_json::to_string_pretty(&manifest).unwrap();
+        assert!(json.contains("\"git-hash\""));
+        assert!(json.contains("\"action\": \"created\""));
+        assert_eq!(serde_json::from_str::<Manifest>(&json).unwrap(), manifest);
+    }
+}
@@ -0,0 +1,374 @@
+//! MCP server configuration files
+//!
+//! Writes or merges MCP (Model Context Protocol) server entries into the
+//! project-scoped configuration file of each supported agent:
+//!
+//! - `.mcp.json` (Claude Code, project scope)
+//! - `opencode.json` (opencode)
+//! - `.gemini/settings.json` (Gemini CLI)
+//!
+//! Existing files are merged: other servers and settings are preserved, and a
+//! server that is already configured under the same name is left as is.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::Path;
+
+use crate::managed::{self, FileChange};
+
+/// An MCP server launched as a local command over stdio
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct McpServer {
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl McpServer {
+    /// The Playwright MCP server for browser automation
+    pub fn playwright() -> Self {
+        Self {
+            name: "playwright".to_string(),
+            command: "npx".to_string(),
+            args: vec!["@playwright/mcp@latest".to_string()],
+        }
+    }
+
+    /// Parse a `NAME=COMMAND [ARGS...]` declaration
+    pub fn parse(declaration: &str) -> Result<Self> {
+        let Some((name, command_line)) = declaration.split_once('=') else {
+            bail!("MCP server must be declared as NAME=COMMAND [ARGS...]: {declaration}");
+        };
+        let name = name.trim();
+        let mut words = command_line.split_whitespace().map(str::to_string);
+        let Some(command) = words.next() else {
+            bail!("MCP server '{name}' has no command");
+        };
+        if name.is_empty() {
+            bail!("MCP server declaration has no name: {declaration}");
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            command,
+            args: words.collect(),
+        })
+    }
+}
+
+/// Configuration file formats for the supported agents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+    /// `{"mcpServers": {"name": {"command": ..., "args": [...]}}}`
+    McpServers,
+    /// `{"mcp": {"name": {"type": "local", "command": [...], "enabled": true}}}`
+    Opencode,
+}
+
+/// Project-scoped MCP configuration files and their formats
+const CONFIG_FILES: [(&str, ConfigFormat); 3] = [
+    (".mcp.json", ConfigFormat::McpServers),
+    ("opencode.json", ConfigFormat::Opencode),
+    (".gemini/settings.json", ConfigFormat::McpServers),
+];
+
+/// Plan writing or merging MCP server entries into each agent's
+/// configuration file
+pub fn plan_mcp_configs(
+    target_path: &Path,
+    servers: &[McpServer],
+    verbose: bool,
+) -> Result<Vec<FileChange>> {
+    let mut changes = Vec::new();
+
+    for (relative_path, format) in CONFIG_FILES {
+        let path = target_path.join(relative_path);
+        let existing = if path.exists() {
+            Some(fs::read_to_string(&path)?)
+        } else {
+            None
+        };
+
+        let Some(content) = merge_config(existing.as_deref(), servers, format, verbose)
+            .with_context(|| format!("Failed to merge MCP servers into {}", path.display()))?
+        else {
+            // Nothing to add: leave the file exactly as it is
+            changes.push(managed::plan_file(
+                &path,
+                existing.as_deref().unwrap_or_default(),
+            )?);
+            continue;
+        };
+
+        changes.push(managed::plan_file(&path, &content)?);
+    }
+
+    Ok(changes)
+}
+
+/// Merge servers into the configuration, returning None if nothing changes
+fn merge_config(
+    existing: Option<&str>,
+    servers: &[McpServer],
+    format: ConfigFormat,
+    verbose: bool,
+) -> Result<Option<String>> {
+    let mut root = match existing {
+        Some(content) if !content.trim().is_empty() => {
+            serde_json::from_str::<Value>(content).context("existing file is not valid JSON")?
+        }
+        _ => initial_config(format),
+    };
+
+    let Some(root_object) = root.as_object_mut() else {
+        bail!("existing file is not a JSON object");
+    };
+
+    let key = server_key(format);
+    let Some(server_map) = root_object
+        .entry(key)
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+    else {
+        bail!("\"{key}\" is not a JSON object");
+    };
+
+    let mut added = false;
+    for server in servers {
+        if server_map.contains_key(&server.name) {
+            if verbose {
+                eprintln!("# Keeping existing MCP server '{}'", server.name);
+            }
+            continue;
+        }
+        server_map.insert(server.name.clone(), server_entry(server, format));
+        added = true;
+    }
+
+    if !added && existing.is_some() {
+        return Ok(None);
+    }
+
+    let mut content = serde_json::to_string_pretty(&root)?;
+    content.push('\n');
+    Ok(Some(content))
+}
+
+/// Whether a path relative to the project root is an MCP configuration file
+pub fn is_config_file(relative_path: &str) -> bool {
+    config_format(relative_path).is_some()
+}
+
+/// Names of the servers configured in an MCP configuration file
+///
+/// Returns an empty list for unknown files and content that does not parse.
+pub fn server_names(relative_path: &str, content: &str) -> Vec<String> {
+    let Some(format) = config_format(relative_path) else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Value>(content)
+        .ok()
+        .and_then(|root| root.get(server_key(format))?.as_object().cloned())
+        .map(|servers| servers.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Remove the named servers from an MCP configuration file
+///
+/// Everything else in the file is kept. Returns None if no server was
+/// removed.
+pub fn remove_servers(
+    relative_path: &str,
+    content: &str,
+    names: &[String],
+) -> Result<Option<String>> {
+    let Some(format) = config_format(relative_path) else {
+        bail!("{relative_path} is not an MCP configuration file");
+    };
+    let mut root =
+        serde_json::from_str::<Value>(content).context("existing file is not valid JSON")?;
+    let Some(server_map) = root
+        .get_mut(server_key(format))
+        .and_then(Value::as_object_mut)
+    else {
+        return Ok(None);
+    };
+
+    let before = server_map.len();
+    for name in names {
+        server_map.shift_remove(name);
+    }
+    if server_map.len() == before {
+        return Ok(None);
+    }
+
+    let mut content = serde_json::to_string_pretty(&root)?;
+    content.push('\n');
+    Ok(Some(content))
+}
+
+/// Format of a configuration file given its path relative to the project root
+fn config_format(relative_path: &str) -> Option<ConfigFormat> {
+    CONFIG_FILES
+        .iter()
+        .find(|(path, _)| *path == relative_path)
+        .map(|(_, format)| *format)
+}
+
+/// Key of the server map in a configuration format
+fn server_key(format: ConfigFormat) -> &'static str {
+    match format {
+        ConfigFormat::McpServers => "mcpServers",
+        ConfigFormat::Opencode => "mcp",
+    }
+}
+
+/// Skeleton for a configuration file that does not exist yet
+fn initial_config(format: ConfigFormat) -> Value {
+    match format {
+        ConfigFormat::McpServers => json!({}),
+        ConfigFormat::Opencode => json!({ "$schema": "https://opencode.ai/config.json" }),
+    }
+}
+
+/// Server entry in the shape the configuration format expects
+fn server_entry(server: &McpServer, format: ConfigFormat) -> Value {
+    match format {
+        ConfigFormat::McpServers => json!({
+            "command": server.command,
+            "args": server.args,
+        }),
+        ConfigFormat::Opencode => {
+            let mut command = vec![server.command.clone()];
+            command.extend(server.args.iter().cloned());
+            json!({
+                "type": "local",
+                "command": command,
+                "enabled": true,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_server_declaration() {
+        let server = McpServer::parse("context7=npx -y @upstash/context7-mcp").unwrap();
+        assert_eq!(server.name, "context7");
+        assert_eq!(server.command, "npx");
+        assert_eq!(server.args, vec!["-y", "@upstash/context7-mcp"]);
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_command() {
+        assert!(McpServer::parse("name=").is_err());
+        assert!(McpServer::parse("no-equals-sign").is_err());
+        assert!(McpServer::parse("=npx").is_err());
+    }
+
+    #[test]
+    fn test_merge_creates_claude_config() {
+        let content = merge_config(
+            None,
+            &[McpServer::playwright()],
+            ConfigFormat::McpServers,
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["mcpServers"]["playwright"]["command"], "npx");
+        assert_eq!(
+            value["mcpServers"]["playwright"]["args"][0],
+            "@playwright/mcp@latest"
+        );
+    }
+
+    #[test]
+    fn test_merge_creates_opencode_config() {
+        let content = merge_config(
+            None,
+            &[McpServer::playwright()],
+            ConfigFormat::Opencode,
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["$schema"], "https://opencode.ai/config.json");
+        assert_eq!(value["mcp"]["playwright"]["type"], "local");
+        assert_eq!(value["mcp"]["playwright"]["command"][0], "npx");
+    }
+
+    #[test]
+    fn test_merge_preserves_other_servers_and_settings() {
+        let existing = r#"{"theme": "dark", "mcpServers": {"github": {"command": "gh-mcp"}}}"#;
+        let content = merge_config(
+            Some(existing),
+            &[McpServer::playwright()],
+            ConfigFormat::McpServers,
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["theme"], "dark");
+        assert_eq!(value["mcpServers"]["github"]["command"], "gh-mcp");
+        assert_eq!(value["mcpServers"]["playwright"]["command"], "npx");
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_server_with_same_name() {
+        let existing = r#"{"mcpServers": {"playwright": {"command": "custom"}}}"#;
+        let merged = merge_config(
+            Some(existing),
+            &[McpServer::playwright()],
+            ConfigFormat::McpServers,
+            false,
+        )
+        .unwrap();
+        assert!(merged.is_none());
+    }
+
+    #[test]
+    fn test_merge_rejects_invalid_json() {
+        assert!(
+            merge_config(
+                Some("{not json"),
+                &[McpServer::playwright()],
+                ConfigFormat::McpServers,
+                false
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_remove_servers_keeps_other_content() {
+        let existing = r#"{"theme": "dark", "mcpServers": {"github": {"command": "gh-mcp"}, "playwright": {"command": "npx"}}}"#;
+        assert_eq!(
+            server_names(".mcp.json", existing),
+            ["github", "playwright"]
+        );
+
+        let content = remove_servers(".mcp.json", existing, &["playwright".to_string()])
+            .unwrap()
+            .unwrap();
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["theme"], "dark");
+        assert_eq!(value["mcpServers"]["github"]["command"], "gh-mcp");
+        assert!(value["mcpServers"].get("playwright").is_none());
+
+        assert!(
+            remove_servers(".mcp.json", &content, &["playwright".to_string()])
+                .unwrap()
+                .is_none()
+        );
+        assert!(server_names("package.json", existing).is_empty());
+    }
+}
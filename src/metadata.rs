@@ -3,31 +3,36 @@
 //! This module handles extracting metadata from project files
 //! (Cargo.toml, package.json, etc.) and the git configuration.
 
+use crate::files::{read_json, read_toml};
 use anyhow::{Context, Result};
+use serde::Serialize;
 use std::path::Path;
 use std::process::Command;
+use toml::{Table, Value};
 
 /// Project metadata extracted from various sources
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct ProjectMetadata {
+    pub project_name: String,
     pub current_year: String,
     pub author_name: String,
-    #[allow(dead_code)] // Used for future features
     pub author_email: Option<String>,
     pub license: String,
-    #[allow(dead_code)] // Used for future features
     pub repository: Option<String>,
 }
 
 impl ProjectMetadata {
     /// Extract metadata for a given project path
     pub fn extract(project_path: &Path) -> Result<Self> {
+        let project_name = extract_project_name(project_path);
         let current_year = get_current_year();
         let (author_name, author_email) = get_git_author()?;
         let license = extract_license(project_path)?;
         let repository = extract_repository(project_path);
 
         Ok(Self {
+            project_name,
             current_year,
             author_name,
             author_email,
@@ -41,8 +46,22 @@ impl ProjectMetadata {
         format!("Copyright (c) {} {}", self.current_year, self.author_name)
     }
 
+    /// Apply license and copyright owner settings from configuration
+    ///
+    /// The `PROACT_C_OWNER` environment variable still takes precedence over
+    /// a configured copyright owner.
+    pub fn apply_overrides(&mut self, license: Option<&str>, copyright_owner: Option<&str>) {
+        if let Some(license) = license {
+            self.license = license.to_string();
+        }
+        if let Some(owner) = copyright_owner
+            && std::env::var_os("PROACT_C_OWNER").is_none()
+        {
+            self.author_name = owner.to_string();
+        }
+    }
+
     /// Get author with email if available
-    #[allow(dead_code)] // Used for future features
     pub fn author_with_email(&self) -> String {
         if let Some(email) = &self.author_email {
             format!("{} <{}>", self.author_name, email)
@@ -100,22 +119,153 @@ fn get_git_author() -> Result<(String, Option<String>)> {
     Ok((name, email))
 }
 
-/// Extract license from Cargo.toml, package.json, or return placeholder
+/// A parsed Cargo.toml together with its workspace root manifest
+///
+/// Fields declared as `key.workspace = true` are looked up in the
+/// `[workspace.package]` table of the workspace root.
+struct CargoManifest {
+    manifest: Table,
+    /// Manifest of the enclosing workspace when it is a different file
+    workspace: Option<Table>,
+}
+
+impl CargoManifest {
+    /// Load Cargo.toml from the project and locate its workspace root
+    fn load(project_path: &Path) -> Option<Self> {
+        let manifest = read_toml(&project_path.join("Cargo.toml"))?;
+        let workspace = if manifest.contains_key("workspace") {
+            None
+        } else {
+            find_workspace_root(project_path, &manifest)
+        };
+        Some(Self {
+            manifest,
+            workspace,
+        })
+    }
+
+    /// Look up a `[package]` field, following workspace inheritance
+    ///
+    /// For a virtual manifest (no `[package]`), `[workspace.package]` is used.
+    fn field(&self, key: &str) -> Option<&Value> {
+        let Some(package) = self.manifest.get("package") else {
+            return self.workspace_field(key);
+        };
+        let value = package.get(key)?;
+        if inherits_from_workspace(value) {
+            self.workspace_field(key)
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Look up a field in `[workspace.package]` of the workspace root
+    fn workspace_field(&self, key: &str) -> Option<&Value> {
+        self.workspace
+            .as_ref()
+            .unwrap_or(&self.manifest)
+            .get("workspace")?
+            .get("package")?
+            .get(key)
+    }
+
+    fn string_field(&self, key: &str) -> Option<String> {
+        non_empty(self.field(key)?.as_str()?)
+    }
+
+    /// SPDX expression from `license`, or a reference to `license-file`
+    fn license(&self) -> Option<String> {
+        self.string_field("license").or_else(|| {
+            self.string_field("license-file")
+                .map(|file| see_license_in(&file))
+        })
+    }
+}
+
+/// Whether a Cargo field is declared as `key.workspace = true`
+fn inherits_from_workspace(value: &Value) -> bool {
+    value.get("workspace").and_then(Value::as_bool) == Some(true)
+}
+
+/// Find the manifest of the workspace that contains the package
+///
+/// Honors an explicit `package.workspace = "path"`, otherwise searches the
+/// parent directories for a Cargo.toml with a `[workspace]` table.
+fn find_workspace_root(project_path: &Path, manifest: &Table) -> Option<Table> {
+    if let Some(root) = manifest
+        .get("package")
+        .and_then(|package| package.get("workspace"))
+        .and_then(Value::as_str)
+    {
+        return read_toml(&project_path.join(root).join("Cargo.toml"));
+    }
+
+    let project_path = std::fs::canonicalize(project_path).ok()?;
+    project_path
+        .ancestors()
+        .skip(1)
+        .filter_map(|dir| read_toml(&dir.join("Cargo.toml")))
+        .find(|manifest| manifest.contains_key("workspace"))
+}
+
+/// npm-style license reference for projects that ship a custom license file
+fn see_license_in(file: &str) -> String {
+    format!("SEE LICENSE IN {file}")
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Extract the project name from Cargo.toml, package.json or pyproject.toml,
+/// falling back to the directory name
+fn extract_project_name(project_path: &Path) -> String {
+    if let Some(name) =
+        CargoManifest::load(project_path).and_then(|manifest| manifest.string_field("name"))
+    {
+        return name;
+    }
+
+    if let Some(name) = read_json(&project_path.join("package.json"))
+        .and_then(|package| package.get("name")?.as_str().and_then(non_empty))
+    {
+        return name;
+    }
+
+    if let Some(name) = read_toml(&project_path.join("pyproject.toml")).and_then(|pyproject| {
+        pyproject_field(&pyproject, "name")?
+            .as_str()
+            .and_then(non_empty)
+    }) {
+        return name;
+    }
+
+    std::fs::canonicalize(project_path)
+        .ok()
+        .as_deref()
+        .unwrap_or(project_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Unknown Project")
+        .to_string()
+}
+
+/// Extract license from Cargo.toml, package.json, pyproject.toml, or return
+/// placeholder
 fn extract_license(project_path: &Path) -> Result<String> {
-    // Try Cargo.toml first
-    let cargo_toml = project_path.join("Cargo.toml");
-    if cargo_toml.exists()
-        && let Ok(content) = std::fs::read_to_string(&cargo_toml)
-        && let Some(license) = extract_license_from_toml(&content)
+    if let Some(license) = CargoManifest::load(project_path).and_then(|m| m.license()) {
+        return Ok(license);
+    }
+
+    if let Some(license) = read_json(&project_path.join("package.json"))
+        .and_then(|package| license_from_json(&package))
     {
         return Ok(license);
     }
 
-    // Try package.json
-    let package_json = project_path.join("package.json");
-    if package_json.exists()
-        && let Ok(content) = std::fs::read_to_string(&package_json)
-        && let Some(license) = extract_license_from_json(&content)
+    if let Some(license) = read_toml(&project_path.join("pyproject.toml"))
+        .and_then(|pyproject| license_from_pyproject(&pyproject))
     {
         return Ok(license);
     }
@@ -124,86 +274,89 @@ fn extract_license(project_path: &Path) -> Result<String> {
     Ok("<license>".to_string())
 }
 
-/// Extract license from TOML content
-fn extract_license_from_toml(content: &str) -> Option<String> {
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("license")
-            && let Some(value) = trimmed.split('=').nth(1)
-        {
-            let license = value.trim().trim_matches('"').trim();
-            if !license.is_empty() {
-                return Some(license.to_string());
-            }
-        }
+/// License from package.json
+///
+/// Accepts `"license": "MIT"`, the legacy `"license": {"type": "MIT"}` object
+/// and the legacy `"licenses"` array (joined as an `OR` expression).
+fn license_from_json(package: &serde_json::Value) -> Option<String> {
+    let license_type = |value: &serde_json::Value| {
+        value
+            .as_str()
+            .or_else(|| value.get("type")?.as_str())
+            .and_then(non_empty)
+    };
+
+    if let Some(license) = package.get("license").and_then(license_type) {
+        return Some(license);
     }
-    None
+
+    let licenses: Vec<String> = package
+        .get("licenses")?
+        .as_array()?
+        .iter()
+        .filter_map(license_type)
+        .collect();
+    (!licenses.is_empty()).then(|| licenses.join(" OR "))
 }
 
-/// Extract license from JSON content (simple extraction)
-fn extract_license_from_json(content: &str) -> Option<String> {
-    // Simple pattern matching for "license": "MIT" or "license": { "type": "MIT" }
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.contains("\"license\"")
-            && let Some(value_part) = trimmed.split(':').nth(1)
-        {
-            let value = value_part
-                .trim()
-                .trim_end_matches(',')
-                .trim_matches('"')
-                .trim();
-            if !value.is_empty() && !value.starts_with('{') {
-                return Some(value.to_string());
-            }
-        }
+/// Look up a PEP 621 `[project]` field, falling back to `[tool.poetry]`
+fn pyproject_field<'a>(pyproject: &'a Table, key: &str) -> Option<&'a Value> {
+    pyproject
+        .get("project")
+        .and_then(|project| project.get(key))
+        .or_else(|| pyproject.get("tool")?.get("poetry")?.get(key))
+}
+
+/// License from pyproject.toml
+///
+/// Accepts a PEP 639 SPDX string, and PEP 621 `{text = "..."}` or
+/// `{file = "..."}` tables.
+fn license_from_pyproject(pyproject: &Table) -> Option<String> {
+    let license = pyproject_field(pyproject, "license")?;
+    if let Some(expression) = license.as_str() {
+        return non_empty(expression);
     }
-    None
+    if let Some(text) = license.get("text").and_then(Value::as_str) {
+        return non_empty(text);
+    }
+    license
+        .get("file")
+        .and_then(Value::as_str)
+        .and_then(non_empty)
+        .map(|file| see_license_in(&file))
 }
 
 /// Extract repository URL from project files
 fn extract_repository(project_path: &Path) -> Option<String> {
-    // Try Cargo.toml
-    let cargo_toml = project_path.join("Cargo.toml");
-    if cargo_toml.exists()
-        && let Ok(content) = std::fs::read_to_string(&cargo_toml)
+    if let Some(repository) =
+        CargoManifest::load(project_path).and_then(|manifest| manifest.string_field("repository"))
     {
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("repository")
-                && let Some(value) = trimmed.split('=').nth(1)
-            {
-                let repo = value.trim().trim_matches('"').trim();
-                if !repo.is_empty() {
-                    return Some(repo.to_string());
-                }
-            }
-        }
+        return Some(repository);
     }
 
-    // Try package.json
-    let package_json = project_path.join("package.json");
-    if package_json.exists()
-        && let Ok(content) = std::fs::read_to_string(&package_json)
-    {
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.contains("\"repository\"")
-                && let Some(value_part) = trimmed.split(':').nth(1)
-            {
-                let repo = value_part
-                    .trim()
-                    .trim_end_matches(',')
-                    .trim_matches('"')
-                    .trim();
-                if !repo.is_empty() && !repo.starts_with('{') {
-                    return Some(repo.to_string());
-                }
-            }
-        }
+    // package.json accepts a URL string or {"type": "git", "url": "..."}
+    if let Some(repository) = read_json(&project_path.join("package.json")).and_then(|package| {
+        let repository = package.get("repository")?;
+        repository
+            .as_str()
+            .or_else(|| repository.get("url")?.as_str())
+            .and_then(non_empty)
+    }) {
+        return Some(repository);
     }
 
-    None
+    // PEP 621 [project.urls] with a conventional key
+    let pyproject = read_toml(&project_path.join("pyproject.toml"))?;
+    let urls = pyproject_field(&pyproject, "urls")?.as_table()?;
+    urls.iter()
+        .find(|(key, _)| {
+            matches!(
+                key.to_lowercase().as_str(),
+                "repository" | "source" | "source code"
+            )
+        })
+        .and_then(|(_, url)| url.as_str())
+        .and_then(non_empty)
 }
 
 /// Generate MIT LICENSE file content
@@ -238,6 +391,19 @@ SOFTWARE.
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::files::setup_test_dir;
+
+    /// License of a project whose Cargo.toml has the given content
+    fn cargo_license(name: &str, content: &str) -> Option<String> {
+        let dir = setup_test_dir("metadata", name);
+        std::fs::write(dir.join("Cargo.toml"), content).unwrap();
+        CargoManifest::load(&dir)?.license()
+    }
+
+    /// License of a package.json with the given content
+    fn json_license(content: &str) -> Option<String> {
+        license_from_json(&serde_json::from_str(content).unwrap())
+    }
 
     #[test]
     fn test_get_current_year() {
@@ -253,7 +419,7 @@ mod tests {
 name = "test"
 license = "MIT"
 "#;
-        assert_eq!(extract_license_from_toml(content), Some("MIT".to_string()));
+        assert_eq!(cargo_license("toml", content), Some("MIT".to_string()));
     }
 
     #[test]
@@ -264,12 +430,129 @@ license = "MIT"
   "license": "MIT"
 }
 "#;
-        assert_eq!(extract_license_from_json(content), Some("MIT".to_string()));
+        assert_eq!(json_license(content), Some("MIT".to_string()));
+    }
+
+    #[test]
+    fn test_extract_license_ignores_license_file_key_order() {
+        let content = r#"
+[package]
+name = "test"
+license-file = "LICENSE.txt"
+license = "Apache-2.0"
+
+[dependencies]
+license-checker = { version = "1" }
+"#;
+        assert_eq!(
+            cargo_license("key_order", content),
+            Some("Apache-2.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_license_file_from_toml() {
+        let content = r#"
+[package]
+name = "test"
+license-file = "LICENSE.txt"
+"#;
+        assert_eq!(
+            cargo_license("license_file", content),
+            Some("SEE LICENSE IN LICENSE.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_license_inherited_from_workspace() {
+        let content = r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+license = "MIT OR Apache-2.0"
+
+[package]
+name = "test"
+license.workspace = true
+"#;
+        assert_eq!(
+            cargo_license("inherited", content),
+            Some("MIT OR Apache-2.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_license_from_json_object_and_minified() {
+        let content = r#"{"name":"test","license":{"type":"ISC","url":"https://example.com"}}"#;
+        assert_eq!(json_license(content), Some("ISC".to_string()));
+
+        let content = r#"{"licenses":[{"type":"MIT"},{"type":"Apache-2.0"}]}"#;
+        assert_eq!(json_license(content), Some("MIT OR Apache-2.0".to_string()));
+    }
+
+    #[test]
+    fn test_license_from_pyproject() {
+        let parse = |content: &str| license_from_pyproject(&toml::from_str(content).unwrap());
+
+        assert_eq!(
+            parse("[project]\nlicense = \"BSD-3-Clause\""),
+            Some("BSD-3-Clause".to_string())
+        );
+        assert_eq!(
+            parse("[project]\nlicense = { text = \"MIT\" }"),
+            Some("MIT".to_string())
+        );
+        assert_eq!(
+            parse("[project]\nlicense = { file = \"LICENSE\" }"),
+            Some("SEE LICENSE IN LICENSE".to_string())
+        );
+        assert_eq!(
+            parse("[tool.poetry]\nlicense = \"GPL-3.0-only\""),
+            Some("GPL-3.0-only".to_string())
+        );
+    }
+
+    #[test]
+    fn test_workspace_member_inherits_from_root() {
+        let root = setup_test_dir("metadata", "workspace");
+        let member = root.join("crates").join("member");
+        std::fs::create_dir_all(&member).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["crates/member"]
+
+[workspace.package]
+license = "Apache-2.0"
+repository = "https://example.com/repo"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            member.join("Cargo.toml"),
+            r#"
+[package]
+name = "member"
+license.workspace = true
+repository = { workspace = true }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(extract_license(&member).unwrap(), "Apache-2.0");
+        assert_eq!(
+            extract_repository(&member).as_deref(),
+            Some("https://example.com/repo")
+        );
+        assert_eq!(extract_project_name(&member), "member");
     }
 
     #[test]
     fn test_copyright_string() {
         let metadata = ProjectMetadata {
+            project_name: "test".to_string(),
             current_year: "2025".to_string(),
             author_name: "John Doe".to_string(),
             author_email: Some("john@example.com".to_string()),
@@ -282,6 +565,7 @@ license = "MIT"
     #[test]
     fn test_author_with_email() {
         let metadata = ProjectMetadata {
+            project_name: "test".to_string(),
             current_year: "2025".to_string(),
             author_name: "John Doe".to_string(),
             author_email: Some("john@example.com".to_string()),
@@ -294,6 +578,7 @@ license = "MIT"
     #[test]
     fn test_generate_mit_license() {
         let metadata = ProjectMetadata {
+            project_name: "test".to_string(),
             current_year: "2025".to_string(),
             author_name: "Test Author".to_string(),
             author_email: None,
@@ -0,0 +1,532 @@
+//! Planning and executing a generation run
+//!
+//! A [`Plan`] holds the change proact would make to every output file. It
+//! is computed by reading the target project without writing anything, so
+//! callers can inspect, filter or diff it before executing it. Dry runs,
+//! diffs and real runs all execute the same [`Operation`]s, so what a dry
+//! run prints is exactly what a real run does.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::agents;
+use crate::config::Settings;
+use crate::diff;
+use crate::generator;
+use crate::learnings::{self, LearningsMerge};
+use crate::license;
+use crate::managed::{self, FileAction, FileChange};
+use crate::manifest;
+use crate::mcp;
+use crate::metadata::ProjectMetadata;
+use crate::workspace;
+
+/// The changes a generation run makes, in the order they are written
+#[derive(Debug, Clone)]
+pub struct Plan {
+    /// Project directory the plan was made for
+    pub target: PathBuf,
+    pub files: Vec<FileChange>,
+    /// Titles of learnings added to an existing learnings.md
+    pub learnings_added: Vec<String>,
+    /// Files proact cannot write as asked, e.g. existing legal files that
+    /// differ from the generated content
+    pub warnings: Vec<String>,
+}
+
+/// A single step of executing a plan
+#[derive(Debug, Clone, Copy)]
+pub enum Operation<'a> {
+    /// Create a directory that does not exist yet
+    CreateDir(&'a Path),
+    /// Create, append to or replace a file
+    Write(&'a FileChange),
+    /// Leave a file as it is
+    Keep(&'a FileChange),
+}
+
+/// How [`Plan::execute`] carries out the operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Execution {
+    /// Describe the operations without writing anything
+    Print,
+    /// Like `Print`, and show a unified diff of each change on stdout
+    Diff,
+    /// Write the files
+    Apply,
+}
+
+impl Plan {
+    /// Compute the change to every output file without writing anything
+    ///
+    /// With `verbose` set, planning decisions (new learnings, kept MCP
+    /// servers, ...) are described on stderr.
+    pub fn new(
+        target: &Path,
+        settings: &Settings,
+        metadata: &ProjectMetadata,
+        verbose: bool,
+    ) -> Result<Self> {
+        let output_dir = settings.output_dir.as_path();
+
+        // Generate the documentation
+        let doc_content =
+            generator::generate_sections(target, &settings.sections, &settings.detectors, verbose)?;
+
+        // The documentation goes into its managed section
+        let output_file = output_dir.join("ai_agent_instructions.md");
+        let mut files = vec![managed::plan_managed(&output_file, &doc_content)?];
+
+        // Render template files (process.md, tools.md)
+        let template_ctx = generator::template_context(target, metadata, &settings.detectors);
+        files.extend(generator::plan_templates(
+            output_dir,
+            &template_ctx,
+            &settings.templates,
+        )?);
+
+        // COPYRIGHT and LICENSE files
+        files.extend(generator::plan_legal_files(
+            target,
+            metadata,
+            settings.overwrite,
+        )?);
+
+        // Merge new learnings into learnings.md
+        let learnings =
+            merge_learnings(output_dir, settings.learnings_library.as_deref(), verbose)?;
+        // A newly created learnings.md gets every entry; only report additions
+        // to an existing one
+        let learnings_added = if learnings.change.action == FileAction::Created {
+            Vec::new()
+        } else {
+            learnings.added
+        };
+        files.push(learnings.change);
+
+        // Agent-specific entry files (CLAUDE.md, AGENTS.md, ...)
+        let docs_dir = output_dir.strip_prefix(target).unwrap_or(output_dir);
+        files.extend(agents::plan_agent_files(
+            target,
+            docs_dir,
+            &settings.agents,
+        )?);
+
+        // Agent instructions in each workspace package (crates/core/AGENTS.md, ...)
+        if settings.per_package {
+            for package in workspace::packages(target) {
+                let content = generator::package_instructions(
+                    target,
+                    &package,
+                    output_dir,
+                    &settings.detectors,
+                );
+                for entry_path in agents::package_entry_paths(&settings.agents) {
+                    let path = target.join(&package.path).join(entry_path);
+                    files.push(managed::plan_managed(&path, &content)?);
+                }
+            }
+        }
+
+        // MCP server configuration (.mcp.json, opencode.json, ...)
+        if !settings.mcp_servers.is_empty() {
+            files.extend(mcp::plan_mcp_configs(
+                target,
+                &settings.mcp_servers,
+                verbose,
+            )?);
+        }
+
+        let warnings = warnings(target, &files, metadata);
+        Ok(Self {
+            target: target.to_path_buf(),
+            files,
+            learnings_added,
+            warnings,
+        })
+    }
+
+    /// Drop changes that would create files, keeping backups of replaced files
+    ///
+    /// Used by `proact update`, which refreshes existing files only.
+    pub fn existing_files_only(mut self) -> Self {
+        let kept: Vec<FileChange> = self
+            .files
+            .iter()
+            .filter(|change| change.action != FileAction::Created)
+            .cloned()
+            .collect();
+        self.files.retain(|change| {
+            change.action != FileAction::Created
+                || kept.iter().any(|other| {
+                    other.action.is_write() && managed::is_backup_of(&change.path, &other.path)
+                })
+        });
+        self
+    }
+
+    /// Whether every file already matches (or deliberately differs from)
+    /// the generated content
+    pub fn is_up_to_date(&self) -> bool {
+        self.files.iter().all(|change| !change.action.is_write())
+    }
+
+    /// The operations that carry out the plan, in order
+    ///
+    /// Each missing directory is created once, before the first file in it.
+    pub fn operations(&self) -> Vec<Operation<'_>> {
+        let mut operations = Vec::new();
+        let mut created: Vec<&Path> = Vec::new();
+        for change in &self.files {
+            if !change.action.is_write() {
+                operations.push(Operation::Keep(change));
+                continue;
+            }
+            if let Some(parent) = change.path.parent()
+                && !parent.as_os_str().is_empty()
+                && !parent.exists()
+                && !created.iter().any(|dir| dir.starts_with(parent))
+            {
+                created.push(parent);
+                operations.push(Operation::CreateDir(parent));
+            }
+            operations.push(Operation::Write(change));
+        }
+        operations
+    }
+
+    /// Carry out the plan: describe, diff or apply every operation
+    ///
+    /// With `verbose` set, each operation is described on stderr as it is
+    /// executed. Warnings are always shown.
+    pub fn execute(&self, execution: Execution, verbose: bool) -> Result<()> {
+        for warning in &self.warnings {
+            eprintln!("⚠️  {warning}");
+        }
+
+        for operation in self.operations() {
+            if verbose {
+                for line in operation.describe() {
+                    eprintln!("{line}");
+                }
+            }
+            match (execution, operation) {
+                (Execution::Apply, Operation::CreateDir(dir)) => fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create {}", dir.display()))?,
+                (Execution::Apply, Operation::Write(change)) => {
+                    fs::write(&change.path, &change.after)
+                        .with_context(|| format!("Failed to write {}", change.path.display()))?
+                }
+                (Execution::Diff, Operation::Write(change)) => {
+                    if let Some(diff) = diff::unified_diff(change, &self.target) {
+                        print!("{diff}");
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the planned changes to disk, creating directories as needed
+    pub fn apply(&self) -> Result<()> {
+        self.execute(Execution::Apply, false)
+    }
+}
+
+impl Operation<'_> {
+    /// Shell-like description of the operation, one line per step
+    pub fn describe(&self) -> Vec<String> {
+        match self {
+            Self::CreateDir(dir) => vec![format!("mkdir -p {}", dir.display())],
+            Self::Keep(change) if change.action == FileAction::Skipped => {
+                vec![format!(
+                    "# Keeping existing file: {}",
+                    change.path.display()
+                )]
+            }
+            Self::Keep(change) => vec![format!("# Up to date: {}", change.path.display())],
+            Self::Write(change) => {
+                let path = change.path.display();
+                let before = change.before.as_deref();
+                match change.action {
+                    FileAction::Appended => {
+                        let existing = before.map_or(0, str::len);
+                        vec![format!(
+                            "append {path} (existing: {existing} bytes + managed section: {} bytes)",
+                            change.after.len() - existing
+                        )]
+                    }
+                    FileAction::Replaced
+                        if before
+                            .is_some_and(|before| managed::strip_section(before).is_some()) =>
+                    {
+                        let mut lines = Vec::new();
+                        if before.is_some_and(managed::was_edited) {
+                            lines.push(format!(
+                                "# Managed section in {path} was edited by hand; replacing it"
+                            ));
+                        }
+                        lines.push(format!(
+                            "update {path} (managed section, {} bytes)",
+                            change.after.len()
+                        ));
+                        lines
+                    }
+                    _ => vec![format!("write {path} ({} bytes)", change.after.len())],
+                }
+            }
+        }
+    }
+}
+
+/// Warnings about files proact cannot write or track as asked
+///
+/// Covers existing files kept because they differ, files outside the
+/// project (which the manifest cannot record) and a declared license proact
+/// has no text for.
+fn warnings(target: &Path, files: &[FileChange], metadata: &ProjectMetadata) -> Vec<String> {
+    let mut warnings: Vec<String> = files
+        .iter()
+        .filter(|change| change.action == FileAction::Skipped)
+        .map(|change| {
+            format!(
+                "{} exists and differs from the generated content; keeping it \
+                 (use --force to overwrite or --backup to save a copy first)",
+                change.path.display()
+            )
+        })
+        .collect();
+    warnings.extend(
+        files
+            .iter()
+            .filter(|change| manifest::relative_path(target, &change.path).is_none())
+            .map(|change| {
+                format!(
+                    "{} is outside the project; it is not recorded in the manifest, \
+                     so `proact clean` will not remove it",
+                    change.path.display()
+                )
+            }),
+    );
+    if let Err(reason) = license::license_files(&metadata.license) {
+        warnings.push(format!("Skipped LICENSE generation: {reason:#}"));
+    }
+    warnings
+}
+
+/// Merge learnings into learnings.md in the output directory
+///
+/// The entries come from the organization-wide learnings library when one
+/// is configured, otherwise from the seed learnings embedded in the binary.
+fn merge_learnings(
+    output_dir: &Path,
+    library: Option<&Path>,
+    verbose: bool,
+) -> Result<LearningsMerge> {
+    let source = match library {
+        Some(library) => {
+            if verbose {
+                eprintln!("# Using learnings library: {}", library.display());
+            }
+            fs::read_to_string(library).with_context(|| {
+                format!("Failed to read learnings library {}", library.display())
+            })?
+        }
+        None => learnings::SEED.to_string(),
+    };
+
+    let target_learnings = output_dir.join("learnings.md");
+    learnings::plan_learnings(&target_learnings, &source, verbose)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::setup_test_dir;
+
+    fn metadata() -> ProjectMetadata {
+        ProjectMetadata {
+            project_name: "demo".to_string(),
+            current_year: "2026".to_string(),
+            author_name: "Test Author".to_string(),
+            author_email: None,
+            license: "MIT".to_string(),
+            repository: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_does_not_write_until_applied() {
+        let target = setup_test_dir("plan", "not-applied");
+        fs::write(target.join("Cargo.toml"), "[package]\nname = \"demo\"").unwrap();
+
+        let settings = Settings::new(&target);
+        let plan = Plan::new(&target, &settings, &metadata(), false).unwrap();
+        assert!(!target.join("docs").exists());
+        assert!(!plan.is_up_to_date());
+        assert!(
+            plan.files
+                .iter()
+                .all(|change| change.action == FileAction::Created)
+        );
+
+        plan.apply().unwrap();
+        assert!(
+            target
+                .join("docs")
+                .join("ai_agent_instructions.md")
+                .exists()
+        );
+        assert!(target.join("LICENSE").exists());
+
+        let again = Plan::new(&target, &settings, &metadata(), false).unwrap();
+        assert!(again.is_up_to_date());
+    }
+
+    #[test]
+    fn test_per_package_instructions() {
+        let target = setup_test_dir("plan", "per-package");
+        fs::create_dir_all(target.join("crates/core")).unwrap();
+        fs::write(
+            target.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]",
+        )
+        .unwrap();
+        fs::write(
+            target.join("crates/core/Cargo.toml"),
+            "[package]\nname = \"demo-core\"",
+        )
+        .unwrap();
+
+        let mut settings = Settings::new(&target);
+        let package_file = target.join("crates/core/AGENTS.md");
+        let plan = Plan::new(&target, &settings, &metadata(), false).unwrap();
+        assert!(!plan.files.iter().any(|change| change.path == package_file));
+
+        settings.per_package = true;
+        settings.agents = vec![agents::Agent::Claude];
+        let plan = Plan::new(&target, &settings, &metadata(), false).unwrap();
+        let change = plan
+            .files
+            .iter()
+            .find(|change| change.path == target.join("crates/core/CLAUDE.md"))
+            .unwrap();
+        assert!(change.after.contains("# demo-core Package Instructions"));
+        assert!(
+            change
+                .after
+                .contains("`../../docs/ai_agent_instructions.md`")
+        );
+        assert!(change.after.contains("- Test: `cargo test`"));
+        assert!(!plan.files.iter().any(|change| change.path == package_file));
+
+        // Documentation outside the workspace, given as an absolute path
+        settings.output_dir = std::path::absolute(target.join("../plan-per-package-docs")).unwrap();
+        let plan = Plan::new(&target, &settings, &metadata(), false).unwrap();
+        let change = plan
+            .files
+            .iter()
+            .find(|change| change.path == target.join("crates/core/CLAUDE.md"))
+            .unwrap();
+        assert!(
+            change
+                .after
+                .contains("`../../../plan-per-package-docs/ai_agent_instructions.md`")
+        );
+    }
+
+    #[test]
+    fn test_existing_files_only() {
+        let target = setup_test_dir("plan", "existing");
+        fs::write(target.join("COPYRIGHT"), "Copyright (c) Someone Else\n").unwrap();
+
+        let mut settings = Settings::new(&target);
+        settings.overwrite = managed::Overwrite::Backup;
+        let plan = Plan::new(&target, &settings, &metadata(), false)
+            .unwrap()
+            .existing_files_only();
+
+        let paths: Vec<&Path> = plan
+            .files
+            .iter()
+            .map(|change| change.path.as_path())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                target.join("COPYRIGHT.bak").as_path(),
+                target.join("COPYRIGHT").as_path()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operations_create_each_directory_once() {
+        let target = setup_test_dir("plan", "operations");
+
+        let mut settings = Settings::new(&target);
+        settings.agents = vec![agents::Agent::Cursor];
+        let plan = Plan::new(&target, &settings, &metadata(), false).unwrap();
+        let operations = plan.operations();
+
+        let dirs: Vec<&Path> = operations
+            .iter()
+            .filter_map(|operation| match operation {
+                Operation::CreateDir(dir) => Some(*dir),
+                _ => None,
+            })
+            .collect();
+        let docs = target.join("docs");
+        let rules = target.join(".cursor").join("rules");
+        assert_eq!(dirs, [docs.as_path(), rules.as_path()]);
+        assert!(matches!(operations[0], Operation::CreateDir(_)));
+        assert_eq!(
+            operations[1].describe(),
+            [format!(
+                "write {} ({} bytes)",
+                plan.files[0].path.display(),
+                plan.files[0].after.len()
+            )]
+        );
+
+        // A dry run executes the same operations without writing
+        plan.execute(Execution::Print, false).unwrap();
+        assert!(!docs.exists());
+        plan.execute(Execution::Apply, false).unwrap();
+        let again = Plan::new(&target, &settings, &metadata(), false).unwrap();
+        assert!(
+            again
+                .operations()
+                .iter()
+                .all(|operation| matches!(operation, Operation::Keep(_)))
+        );
+    }
+
+    #[test]
+    fn test_warnings() {
+        let skipped = FileChange {
+            path: PathBuf::from("COPYRIGHT"),
+            action: FileAction::Skipped,
+            before: Some("ours".to_string()),
+            after: "ours".to_string(),
+        };
+        let outside = FileChange {
+            path: PathBuf::from("../docs/process.md"),
+            action: FileAction::Created,
+            before: None,
+            after: "generated".to_string(),
+        };
+        let target = Path::new("");
+        assert!(warnings(target, &[], &metadata()).is_empty());
+
+        let mut gpl = metadata();
+        gpl.license = "GPL-3.0-only".to_string();
+        let warnings = warnings(target, &[skipped, outside], &gpl);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].starts_with("COPYRIGHT exists"));
+        assert!(warnings[1].starts_with("../docs/process.md is outside the project"));
+        assert!(warnings[2].contains("GPL-3.0-only"));
+    }
+}
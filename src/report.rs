@@ -0,0 +1,157 @@
+//! Structured run reports
+//!
+//! With `--format json` every command prints a single JSON report on stdout
+//! instead of the text summary, so bootstrap scripts and agents can consume
+//! proact results programmatically. Progress and verbose output still go to
+//! stderr.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::clean::{CleanAction, Cleaned};
+use crate::diff;
+use crate::generator::DocSection;
+use crate::managed::{FileAction, FileChange};
+use crate::metadata::ProjectMetadata;
+
+/// How a command reports its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable summary
+    #[default]
+    Text,
+    /// A single JSON report on stdout
+    Json,
+}
+
+/// What happened to a file, for generation or clean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Action {
+    File(FileAction),
+    Clean(CleanAction),
+}
+
+/// A file in the report
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub action: Action,
+    /// Size of the file after the run (not reported by clean)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<usize>,
+    /// Unified diff of the change, with `--diff`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+impl FileReport {
+    /// Report a generated file, with its diff relative to `target` if asked
+    pub fn from_change(change: &FileChange, target: &Path, with_diff: bool) -> Self {
+        Self {
+            path: change.path.clone(),
+            action: Action::File(change.action),
+            bytes: Some(change.after.len()),
+            diff: with_diff
+                .then(|| diff::unified_diff(change, target))
+                .flatten(),
+        }
+    }
+
+    /// Report a file changed by clean
+    pub fn from_cleaned(cleaned: &Cleaned) -> Self {
+        Self {
+            path: cleaned.path.clone(),
+            action: Action::Clean(cleaned.action),
+            bytes: None,
+            diff: None,
+        }
+    }
+}
+
+/// The result of a command
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Report {
+    pub command: &'static str,
+    pub target: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<PathBuf>,
+    pub dry_run: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub project_types: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ProjectMetadata>,
+    pub files: Vec<FileReport>,
+    /// Titles of the learnings the command added
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub learnings_added: Vec<String>,
+    /// Whether every generated file is current (check only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub up_to_date: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<DocSection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    /// An empty report for a command run on `target`
+    pub fn new(command: &'static str, target: &Path) -> Self {
+        Self {
+            command,
+            target: target.to_path_buf(),
+            output_dir: None,
+            dry_run: false,
+            project_types: Vec::new(),
+            metadata: None,
+            files: Vec::new(),
+            learnings_added: Vec::new(),
+            up_to_date: None,
+            section: None,
+            content: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Print the report as pretty JSON on stdout
+    pub fn print(&self) -> Result<()> {
+        println!("{}", serde_json::to_string_pretty(self)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_serializes_actions() {
+        let mut report = Report::new("init", Path::new("project"));
+        report.files.push(FileReport::from_change(
+            &FileChange {
+                path: PathBuf::from("project/LICENSE"),
+                action: FileAction::Created,
+                before: None,
+                after: "MIT".to_string(),
+            },
+            Path::new("project"),
+            false,
+        ));
+        report.files.push(FileReport::from_cleaned(&Cleaned {
+            path: PathBuf::from("project/CLAUDE.md"),
+            action: CleanAction::Stripped,
+        }));
+
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["command"], "init");
+        assert_eq!(value["files"][0]["action"], "created");
+        assert_eq!(value["files"][0]["bytes"], 3);
+        assert_eq!(value["files"][1]["action"], "stripped");
+        assert!(value.get("metadata").is_none());
+        assert_eq!(value["warnings"], serde_json::json!([]));
+    }
+}
@@ -3,6 +3,151 @@
 //! This module contains the templates used to generate comprehensive
 //! documentation for AI coding agents, including process guidelines,
 //! continuous improvement practices, and tool setup instructions.
+//!
+//! It also provides a small template engine used to render the embedded
+//! `process.md` and `tools.md` files with project-specific variables.
+
+use anyhow::{Result, bail};
+use std::collections::BTreeMap;
+
+/// Variables available to templates during rendering
+///
+/// A variable is considered true in `{{#if name}}` blocks when it is set to a
+/// non-empty value.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    vars: BTreeMap<String, String>,
+}
+
+impl TemplateContext {
+    /// Create an empty context
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a variable value
+    pub fn set(&mut self, name: &str, value: impl Into<String>) {
+        self.vars.insert(name.to_string(), value.into());
+    }
+
+    /// Set a boolean flag for use in conditional blocks
+    pub fn set_flag(&mut self, name: &str, enabled: bool) {
+        if enabled {
+            self.set(name, "true");
+        } else {
+            self.vars.remove(name);
+        }
+    }
+
+    /// Get a variable value
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    /// Whether a variable is set to a non-empty value
+    pub fn is_true(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|v| !v.is_empty())
+    }
+}
+
+/// Render a template with the given context
+///
+/// Supported syntax:
+/// - `{{name}}` is replaced with the variable value. Unknown variables and
+///   anything that is not a plain identifier (e.g. GitHub Actions
+///   `${{ matrix.os }}`) are left untouched.
+/// - `{{#if name}}`, `{{else}}` and `{{/if}}` on their own lines form
+///   conditional blocks, which may be nested. `{{#unless name}}` is the
+///   negated form and is closed with `{{/unless}}`.
+///
+/// Closing a block with the other kind's tag is an error.
+pub fn render(template: &str, ctx: &TemplateContext) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    // Each entry records the tag that closes the block, whether the enclosing
+    // output is active and whether the current branch of this block is active.
+    let mut stack: Vec<(&str, bool, bool)> = Vec::new();
+    let mut active = true;
+
+    for (number, line) in template.split_inclusive('\n').enumerate() {
+        let tag = line.trim();
+        if let Some(name) = block_tag(tag, "#if") {
+            let branch = ctx.is_true(name);
+            stack.push(("{{/if}}", active, branch));
+            active = active && branch;
+        } else if let Some(name) = block_tag(tag, "#unless") {
+            let branch = !ctx.is_true(name);
+            stack.push(("{{/unless}}", active, branch));
+            active = active && branch;
+        } else if tag == "{{else}}"
+            && let Some((close, parent, branch)) = stack.pop()
+        {
+            stack.push((close, parent, !branch));
+            active = parent && !branch;
+        } else if (tag == "{{/if}}" || tag == "{{/unless}}")
+            && let Some((close, parent, _)) = stack.pop()
+        {
+            if tag != close {
+                bail!(
+                    "line {}: {tag} closes a block opened by {close}",
+                    number + 1
+                );
+            }
+            active = parent;
+        } else if active {
+            output.push_str(&substitute(line, ctx));
+        }
+    }
+
+    Ok(output)
+}
+
+/// Parse a standalone block tag such as `{{#if name}}`, returning the name
+fn block_tag<'a>(tag: &'a str, keyword: &str) -> Option<&'a str> {
+    let name = tag
+        .strip_prefix("{{")?
+        .strip_suffix("}}")?
+        .strip_prefix(keyword)?
+        .strip_prefix(' ')?
+        .trim();
+    is_identifier(name).then_some(name)
+}
+
+/// Replace `{{name}}` placeholders in a single line
+fn substitute(line: &str, ctx: &TemplateContext) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) if is_identifier(&after[..end]) => {
+                let name = &after[..end];
+                match ctx.get(name) {
+                    Some(value) => result.push_str(value),
+                    None => {
+                        result.push_str("{{");
+                        result.push_str(name);
+                        result.push_str("}}");
+                    }
+                }
+                rest = &after[end + 2..];
+            }
+            _ => {
+                result.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Template variable names are ASCII letters, digits and underscores
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
 
 /// Returns the process guidelines template
 pub fn process_guidelines() -> &'static str {
@@ -200,7 +345,7 @@ All Playwright MCP tools follow a consistent naming pattern:
 
 ### Best Practices
 1. **Use User-Level Setup**: Configure frequently-used MCP servers at user level for consistency
-2. **Project-Level for Specific Needs**: Use project-level configuration only for project-specific tools
+2. **Project-Level for Specific Needs**: Use project-level configuration only for project-specific tools (`proact --mcp` writes `.mcp.json`, `opencode.json` and `.gemini/settings.json`)
 3. **Regular Updates**: Periodically update the MCP server to get latest features
 4. **Consistent Naming**: Always use "playwright" as the server name for consistency
 
@@ -279,4 +424,45 @@ mod tests {
         assert!(!summary().is_empty());
         assert!(summary().contains("Expected Proactive Behaviors"));
     }
+
+    #[test]
+    fn test_render_substitutes_variables() {
+        let mut ctx = TemplateContext::new();
+        ctx.set("project_name", "demo");
+        let rendered = render("Project {{project_name}} uses {{unknown}}\n", &ctx).unwrap();
+        assert_eq!(rendered, "Project demo uses {{unknown}}\n");
+    }
+
+    #[test]
+    fn test_render_leaves_expressions_untouched() {
+        let ctx = TemplateContext::new();
+        let template = "runs-on: ${{ matrix.os }}\n";
+        assert_eq!(render(template, &ctx).unwrap(), template);
+    }
+
+    #[test]
+    fn test_render_conditional_blocks() {
+        let mut ctx = TemplateContext::new();
+        ctx.set_flag("rust", true);
+        let template = "a\n{{#if rust}}\nrust\n{{#if wasm}}\nwasm\n{{/if}}\n{{else}}\nother\n{{/if}}\n{{#unless wasm}}\nno wasm\n{{/unless}}\nb\n";
+        assert_eq!(render(template, &ctx).unwrap(), "a\nrust\nno wasm\nb\n");
+    }
+
+    #[test]
+    fn test_render_rejects_mismatched_close() {
+        let ctx = TemplateContext::new();
+        let error = render("{{#if rust}}\nrust\n{{/unless}}\n", &ctx).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: {{/unless}} closes a block opened by {{/if}}"
+        );
+        assert!(render("{{#unless wasm}}\n{{/if}}\n", &ctx).is_err());
+    }
+
+    #[test]
+    fn test_render_else_branch() {
+        let ctx = TemplateContext::new();
+        let template = "{{#if rust}}\nrust\n{{else}}\nother\n{{/if}}\n";
+        assert_eq!(render(template, &ctx).unwrap(), "other\n");
+    }
 }
@@ -0,0 +1,478 @@
+//! Workspace and monorepo packages
+//!
+//! A Cargo workspace, a pnpm/npm/yarn workspace or a polyglot monorepo holds
+//! several packages below its root. Members are discovered from:
+//!
+//! - Cargo.toml `[workspace] members` (minus `exclude`)
+//! - pnpm-workspace.yaml `packages`
+//! - package.json `workspaces`
+//! - pyproject.toml files in subdirectories
+//!
+//! Member patterns may use `*` within a path segment and `**` for any
+//! number of directories (up to a fixed depth, without following symlinks);
+//! patterns starting with `!` exclude packages.
+
+use crate::files::{read_json, read_toml};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use toml::Value;
+
+/// Directories never searched for nested pyproject.toml files
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "venv",
+    "build",
+    "dist",
+    "__pycache__",
+];
+
+/// How deep below the root nested pyproject.toml files are searched
+const PYPROJECT_DEPTH: usize = 3;
+
+/// How many directories below the root a `**` member pattern descends
+const WILDCARD_DEPTH: usize = 8;
+
+/// A package inside a workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Package {
+    /// Package directory relative to the workspace root, `/`-separated
+    pub path: String,
+    /// Name from the package manifest, or the directory name
+    pub name: String,
+    /// Kind of workspace that declares the package (`cargo`, `pnpm`, `npm`
+    /// or `python`)
+    pub workspace: &'static str,
+}
+
+/// Packages of the workspace rooted at `target_path`, sorted by path
+///
+/// A directory declared by several workspaces is listed once, under the
+/// first of cargo, pnpm, npm and python. Returns an empty list for a
+/// project that is not a workspace.
+pub fn packages(target_path: &Path) -> Vec<Package> {
+    let mut packages: Vec<Package> = Vec::new();
+    let sources = [
+        ("cargo", "Cargo.toml", cargo_members(target_path)),
+        ("pnpm", "package.json", pnpm_members(target_path)),
+        ("npm", "package.json", npm_members(target_path)),
+        ("python", "pyproject.toml", pyprojects(target_path)),
+    ];
+
+    for (workspace, manifest, paths) in sources {
+        for path in paths {
+            let dir = target_path.join(&path);
+            if path.is_empty()
+                || !dir.join(manifest).is_file()
+                || packages.iter().any(|package| package.path == path)
+            {
+                continue;
+            }
+            packages.push(Package {
+                name: package_name(&dir, manifest).unwrap_or_else(|| last_segment(&path)),
+                path,
+                workspace,
+            });
+        }
+    }
+
+    packages.sort_by(|a, b| a.path.cmp(&b.path));
+    packages
+}
+
+/// Members of a Cargo workspace
+fn cargo_members(target_path: &Path) -> Vec<String> {
+    let Some(workspace) = read_toml(&target_path.join("Cargo.toml"))
+        .and_then(|manifest| manifest.get("workspace").and_then(Value::as_table).cloned())
+    else {
+        return Vec::new();
+    };
+    let patterns = string_array(workspace.get("members"))
+        .into_iter()
+        .chain(
+            string_array(workspace.get("exclude"))
+                .into_iter()
+                .map(|path| format!("!{path}")),
+        )
+        .collect::<Vec<_>>();
+    expand_patterns(target_path, &patterns)
+}
+
+/// Members listed under `packages:` in pnpm-workspace.yaml
+fn pnpm_members(target_path: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(target_path.join("pnpm-workspace.yaml")) else {
+        return Vec::new();
+    };
+    expand_patterns(target_path, &yaml_list(&content, "packages"))
+}
+
+/// Members listed in package.json `workspaces`, either an array or an
+/// object with a `packages` array (yarn)
+fn npm_members(target_path: &Path) -> Vec<String> {
+    let Some(manifest) = read_json(&target_path.join("package.json")) else {
+        return Vec::new();
+    };
+    let workspaces = match manifest.get("workspaces") {
+        Some(serde_json::Value::Object(workspaces)) => workspaces.get("packages"),
+        workspaces => workspaces,
+    };
+    let patterns: Vec<String> = workspaces
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|pattern| pattern.as_str().map(String::from))
+        .collect();
+    expand_patterns(target_path, &patterns)
+}
+
+/// Subdirectories containing a pyproject.toml
+fn pyprojects(target_path: &Path) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut pending = vec![(String::new(), 0)];
+    while let Some((path, depth)) = pending.pop() {
+        if depth > 0 && target_path.join(&path).join("pyproject.toml").is_file() {
+            found.push(path.clone());
+        }
+        if depth == PYPROJECT_DEPTH {
+            continue;
+        }
+        for name in subdirectories(&target_path.join(&path)) {
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            pending.push((join(&path, &name), depth + 1));
+        }
+    }
+    found
+}
+
+/// Directories matching the patterns, minus those matching a `!` pattern
+fn expand_patterns(target_path: &Path, patterns: &[String]) -> Vec<String> {
+    let (excludes, includes): (Vec<&String>, Vec<&String>) = patterns
+        .iter()
+        .partition(|pattern| pattern.starts_with('!'));
+    let excludes: Vec<Vec<&str>> = excludes
+        .iter()
+        .map(|pattern| segments(&pattern[1..]))
+        .collect();
+
+    let mut paths = Vec::new();
+    for pattern in includes {
+        for path in expand(target_path, &segments(pattern)) {
+            let excluded = excludes
+                .iter()
+                .any(|exclude| path_matches(exclude, &path.split('/').collect::<Vec<_>>()));
+            if !excluded && !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// Directories below `target_path` matching the pattern segments
+fn expand(target_path: &Path, pattern: &[&str]) -> Vec<String> {
+    let mut matches = Vec::new();
+    let mut pending = vec![(String::new(), pattern)];
+    while let Some((path, remaining)) = pending.pop() {
+        let Some((segment, rest)) = remaining.split_first() else {
+            if target_path.join(&path).is_dir() {
+                matches.push(path);
+            }
+            continue;
+        };
+        if *segment == "**" {
+            // Zero directories, or one more directory and `**` again
+            pending.push((path.clone(), rest));
+            if path.split('/').count() >= WILDCARD_DEPTH {
+                continue;
+            }
+            for name in subdirectories(&target_path.join(&path)) {
+                if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                    pending.push((join(&path, &name), remaining));
+                }
+            }
+        } else if segment.contains(['*', '?']) {
+            for name in subdirectories(&target_path.join(&path)) {
+                if wildcard_matches(segment, &name) {
+                    pending.push((join(&path, &name), rest));
+                }
+            }
+        } else {
+            pending.push((join(&path, segment), rest));
+        }
+    }
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+/// Whether a `/`-separated path matches the pattern segments
+fn path_matches(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, None) => true,
+        (Some((&"**", rest)), _) => {
+            path_matches(rest, path) || (!path.is_empty() && path_matches(pattern, &path[1..]))
+        }
+        (Some((segment, rest)), Some((name, path))) => {
+            wildcard_matches(segment, name) && path_matches(rest, path)
+        }
+        _ => false,
+    }
+}
+
+/// Match a single path segment against a pattern with `*` and `?`
+fn wildcard_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // matches[j]: whether the pattern so far matches name[..j]
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for token in pattern {
+        let mut next = vec![false; name.len() + 1];
+        for j in 0..=name.len() {
+            next[j] = match token {
+                '*' => matches[j] || (j > 0 && next[j - 1]),
+                '?' => j > 0 && matches[j - 1],
+                c => j > 0 && matches[j - 1] && name[j - 1] == c,
+            };
+        }
+        matches = next;
+    }
+    matches[name.len()]
+}
+
+/// Path segments of a pattern, ignoring `.` and empty segments
+fn segments(pattern: &str) -> Vec<&str> {
+    pattern
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect()
+}
+
+/// Items of a top-level list in a YAML file, e.g. `packages:` followed by
+/// `- 'apps/*'` lines or an inline `[a, b]` list
+fn yaml_list(content: &str, key: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut in_list = false;
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or_default().trim_end();
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if let Some(value) = line
+            .strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix(':'))
+        {
+            in_list = true;
+            if let Some(inline) = value.trim().strip_prefix('[') {
+                items.extend(
+                    inline
+                        .trim_end_matches(']')
+                        .split(',')
+                        .map(unquote)
+                        .filter(|item| !item.is_empty()),
+                );
+                in_list = false;
+            }
+        } else if in_list {
+            match line.trim_start().strip_prefix('-') {
+                Some(item) if line.starts_with([' ', '-']) => items.push(unquote(item)),
+                _ => in_list = false,
+            }
+        }
+    }
+    items
+}
+
+/// Strip whitespace and YAML quotes from a list item
+fn unquote(item: &str) -> String {
+    item.trim().trim_matches(['\'', '"']).to_string()
+}
+
+/// Name of the package from its manifest
+fn package_name(dir: &Path, manifest: &str) -> Option<String> {
+    let name = match manifest {
+        "Cargo.toml" => read_toml(&dir.join(manifest))?
+            .get("package")?
+            .get("name")?
+            .as_str()?
+            .to_string(),
+        "package.json" => read_json(&dir.join(manifest))?
+            .get("name")?
+            .as_str()?
+            .to_string(),
+        _ => {
+            let pyproject = read_toml(&dir.join(manifest))?;
+            let project = pyproject
+                .get("project")
+                .or_else(|| pyproject.get("tool").and_then(|tool| tool.get("poetry")))?;
+            project.get("name")?.as_str()?.to_string()
+        }
+    };
+    Some(name)
+}
+
+/// Strings of a TOML array, skipping other values
+fn string_array(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str().map(String::from))
+        .collect()
+}
+
+/// Names of the subdirectories of `dir`, sorted
+///
+/// Symlinks are not followed, so a link back up the tree cannot make the
+/// search loop.
+fn subdirectories(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Append a segment to a `/`-separated relative path
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}/{name}")
+    }
+}
+
+/// Last segment of a `/`-separated path
+fn last_segment(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::setup_test_dir;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn paths(packages: &[Package]) -> Vec<&str> {
+        packages
+            .iter()
+            .map(|package| package.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_cargo_workspace_members() {
+        let dir = setup_test_dir("workspace", "cargo");
+        write(
+            &dir,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\nexclude = [\"crates/old\"]\n",
+        );
+        write(
+            &dir,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"acme-core\"\n",
+        );
+        write(
+            &dir,
+            "crates/web/Cargo.toml",
+            "[package]\nname = \"acme-web\"\n",
+        );
+        write(&dir, "crates/old/Cargo.toml", "[package]\nname = \"old\"\n");
+        // Matched by the glob but not a package
+        fs::create_dir_all(dir.join("crates/notes")).unwrap();
+        write(&dir, "tools/cli/Cargo.toml", "");
+
+        let packages = packages(&dir);
+        assert_eq!(paths(&packages), ["crates/core", "crates/web", "tools/cli"]);
+        assert_eq!(packages[0].name, "acme-core");
+        assert_eq!(packages[0].workspace, "cargo");
+        // No package name: the directory name is used
+        assert_eq!(packages[2].name, "cli");
+    }
+
+    #[test]
+    fn test_javascript_workspaces() {
+        let dir = setup_test_dir("workspace", "javascript");
+        write(
+            &dir,
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'apps/*'\n  - \"!apps/legacy\" # retired\ncatalog:\n  react: ^18\n",
+        );
+        write(&dir, "apps/web/package.json", r#"{"name": "@acme/web"}"#);
+        write(&dir, "apps/legacy/package.json", "{}");
+        write(
+            &dir,
+            "package.json",
+            r#"{"workspaces": {"packages": ["libs/**"]}}"#,
+        );
+        write(&dir, "libs/ui/button/package.json", r#"{"name": "button"}"#);
+
+        let packages = packages(&dir);
+        assert_eq!(paths(&packages), ["apps/web", "libs/ui/button"]);
+        assert_eq!(packages[0].name, "@acme/web");
+        assert_eq!(packages[0].workspace, "pnpm");
+        assert_eq!(packages[1].workspace, "npm");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_recursive_patterns_skip_symlinks_and_stop() {
+        let dir = setup_test_dir("workspace", "symlink-loop");
+        write(&dir, "package.json", r#"{"workspaces": ["libs/**"]}"#);
+        write(&dir, "libs/ui/package.json", r#"{"name": "ui"}"#);
+        std::os::unix::fs::symlink("..", dir.join("libs/ui/loop")).unwrap();
+        let deep = (0..WILDCARD_DEPTH).fold(String::from("libs"), |path, n| format!("{path}/d{n}"));
+        write(&dir, &format!("{deep}/package.json"), "{}");
+
+        assert_eq!(paths(&packages(&dir)), ["libs/ui"]);
+    }
+
+    #[test]
+    fn test_nested_pyprojects() {
+        let dir = setup_test_dir("workspace", "python");
+        write(&dir, "pyproject.toml", "[project]\nname = \"root\"\n");
+        write(
+            &dir,
+            "services/api/pyproject.toml",
+            "[project]\nname = \"acme-api\"\n",
+        );
+        write(&dir, "node_modules/x/pyproject.toml", "");
+        write(&dir, ".venv/lib/pyproject.toml", "");
+
+        let packages = packages(&dir);
+        assert_eq!(paths(&packages), ["services/api"]);
+        assert_eq!(packages[0].name, "acme-api");
+    }
+
+    #[test]
+    fn test_single_project_has_no_packages() {
+        let dir = setup_test_dir("workspace", "single");
+        write(&dir, "Cargo.toml", "[package]\nname = \"single\"\n");
+        write(&dir, "package.json", "{}");
+        assert!(packages(&dir).is_empty());
+    }
+
+    #[test]
+    fn test_wildcards() {
+        assert!(wildcard_matches("*", "core"));
+        assert!(wildcard_matches("acme-*", "acme-web"));
+        assert!(!wildcard_matches("acme-*", "web"));
+        assert!(wildcard_matches("v?", "v2"));
+        assert!(path_matches(&["libs", "**"], &["libs", "ui", "button"]));
+        assert!(!path_matches(&["apps", "*"], &["apps", "web", "src"]));
+    }
+}
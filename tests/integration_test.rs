@@ -278,27 +278,738 @@ fn test_learnings_append_to_existing() {
         String::from_utf8_lossy(&output.stderr)
     );
 
-    // Check that learnings.md was appended, not replaced
+    // Check that learning entries were appended, not replaced
     let learnings_content = fs::read_to_string(docs_dir.join("learnings.md")).unwrap();
     assert!(
-        learnings_content.contains("Existing Learnings"),
+        learnings_content.starts_with(existing_content),
         "Original content should be preserved"
     );
     assert!(
-        learnings_content.contains("---- Added"),
-        "Should have separator with timestamp"
+        learnings_content.contains("### 1. Documentation Comment Style"),
+        "New entries should be appended"
     );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("📚 New learning: Documentation Comment Style"));
+
+    // A second run adds nothing
+    let output = Command::new("cargo")
+        .args(["run", "--", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let rerun = fs::read_to_string(docs_dir.join("learnings.md")).unwrap();
+    assert_eq!(rerun, learnings_content, "Entries should not be duplicated");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("New learning"));
+
+    // No cleanup - leave for inspection
+}
+
+#[test]
+fn test_regeneration_is_idempotent() {
+    let test_dir = setup_test_dir("regeneration_idempotent");
+
+    let target_dir = test_dir.join("project");
+    let docs_dir = target_dir.join("docs");
+    fs::create_dir_all(&docs_dir).unwrap();
+    fs::write(target_dir.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(
+        docs_dir.join("process.md"),
+        "# Team Process\n\nOur own notes.\n",
+    )
+    .unwrap();
+
+    for _ in 0..3 {
+        let output = Command::new("cargo")
+            .args(["run", "--", target_dir.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            output.status.success(),
+            "Command failed: {:?}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    for name in ["process.md", "tools.md", "ai_agent_instructions.md"] {
+        let content = fs::read_to_string(docs_dir.join(name)).unwrap();
+        assert_eq!(
+            content
+                .lines()
+                .filter(|line| line.starts_with("<!-- proact:begin"))
+                .count(),
+            1,
+            "{name} should contain exactly one managed section"
+        );
+    }
+
+    let learnings = fs::read_to_string(docs_dir.join("learnings.md")).unwrap();
+    assert_eq!(learnings.matches("### 1. ").count(), 1);
+
+    let process = fs::read_to_string(docs_dir.join("process.md")).unwrap();
     assert!(
-        learnings_content.contains("Learnings from Development Issues"),
-        "New content should be appended"
+        process.starts_with("# Team Process\n\nOur own notes.\n"),
+        "User content outside the markers should be preserved"
     );
+}
 
-    // No cleanup - leave for inspection
+#[test]
+fn test_agents_generates_entry_files() {
+    let test_dir = setup_test_dir("agent_entry_files");
+
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(target_dir.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(target_dir.join("CLAUDE.md"), "# Project notes\n").unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "--agents",
+            "all",
+            "-o",
+            "agent-docs",
+            target_dir.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let claude = fs::read_to_string(target_dir.join("CLAUDE.md")).unwrap();
+    assert!(
+        claude.starts_with("# Project notes\n"),
+        "Existing CLAUDE.md content should be preserved"
+    );
+    assert!(claude.contains("@agent-docs/ai_agent_instructions.md"));
+
+    let gemini = fs::read_to_string(target_dir.join("GEMINI.md")).unwrap();
+    assert!(gemini.contains("@agent-docs/process.md"));
+
+    let agents = fs::read_to_string(target_dir.join("AGENTS.md")).unwrap();
+    assert!(agents.contains("Process-Oriented Workflow"));
+
+    let copilot =
+        fs::read_to_string(target_dir.join(".github").join("copilot-instructions.md")).unwrap();
+    assert!(copilot.contains("`agent-docs/learnings.md`"));
+
+    let cursor =
+        fs::read_to_string(target_dir.join(".cursor").join("rules").join("proact.mdc")).unwrap();
+    assert!(
+        cursor.starts_with("---\n"),
+        "Cursor rules need front matter"
+    );
+}
+
+#[test]
+fn test_per_package_workspace_instructions() {
+    let test_dir = setup_test_dir("per_package");
+
+    let target_dir = test_dir.join("workspace");
+    let member_dir = target_dir.join("crates").join("core");
+    fs::create_dir_all(&member_dir).unwrap();
+    fs::write(
+        target_dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        member_dir.join("Cargo.toml"),
+        "[package]\nname = \"demo-core\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--per-package", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let instructions =
+        fs::read_to_string(target_dir.join("docs").join("ai_agent_instructions.md")).unwrap();
+    assert!(instructions.contains("### Project Map"));
+    assert!(instructions.contains("| demo-core | `crates/core` | Rust |"));
+
+    let package = fs::read_to_string(member_dir.join("AGENTS.md")).unwrap();
+    assert!(package.contains("# demo-core Package Instructions"));
+    assert!(package.contains("`../../docs/ai_agent_instructions.md`"));
 }
 
 #[test]
-fn test_dry_run_skips_missing_learnings() {
-    let test_dir = setup_test_dir("dry_run_no_learnings");
+fn test_mcp_merges_existing_config() {
+    let test_dir = setup_test_dir("mcp_merge");
+
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(target_dir.join("package.json"), "{}").unwrap();
+    fs::write(
+        target_dir.join(".mcp.json"),
+        r#"{"mcpServers": {"github": {"command": "gh-mcp"}}}"#,
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "--mcp-server",
+            "context7=npx -y @upstash/context7-mcp",
+            target_dir.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let claude = fs::read_to_string(target_dir.join(".mcp.json")).unwrap();
+    assert!(claude.contains("\"github\""), "Other servers must be kept");
+    assert!(claude.contains("\"playwright\""));
+    assert!(claude.contains("@upstash/context7-mcp"));
+
+    let opencode = fs::read_to_string(target_dir.join("opencode.json")).unwrap();
+    assert!(opencode.contains("\"type\": \"local\""));
+
+    let gemini = fs::read_to_string(target_dir.join(".gemini").join("settings.json")).unwrap();
+    assert!(gemini.contains("\"mcpServers\""));
+}
+
+#[test]
+fn test_dual_license_generates_license_pair() {
+    let test_dir = setup_test_dir("dual_license");
+
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(
+        target_dir.join("Cargo.toml"),
+        "[package]\nname = \"demo\"\nlicense = \"MIT OR Apache-2.0\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    assert!(!target_dir.join("LICENSE").exists());
+    let mit = fs::read_to_string(target_dir.join("LICENSE-MIT")).unwrap();
+    assert!(mit.starts_with("MIT License"));
+    let apache = fs::read_to_string(target_dir.join("LICENSE-APACHE")).unwrap();
+    assert!(apache.contains("Apache License"));
+    assert!(apache.contains("Version 2.0, January 2004"));
+}
+
+#[test]
+fn test_existing_license_is_not_overwritten() {
+    let test_dir = setup_test_dir("existing_license");
+
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(target_dir.join("Cargo.toml"), "[package]\nname = \"demo\"").unwrap();
+    fs::write(target_dir.join("LICENSE"), "Customer license terms\n").unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("Skipped (existing file differs)"));
+    assert!(stderr.contains("--force"));
+    assert_eq!(
+        fs::read_to_string(target_dir.join("LICENSE")).unwrap(),
+        "Customer license terms\n"
+    );
+
+    // --backup keeps a copy before replacing
+    let output = Command::new("cargo")
+        .args(["run", "--", "--backup", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(target_dir.join("LICENSE.bak")).unwrap(),
+        "Customer license terms\n"
+    );
+    assert!(
+        fs::read_to_string(target_dir.join("LICENSE"))
+            .unwrap()
+            .starts_with("MIT License")
+    );
+}
+
+#[test]
+fn test_learn_records_entry() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let test_dir = setup_test_dir("learn");
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(&target_dir).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "learn",
+            target_dir.to_str().unwrap(),
+            "--title",
+            "Flaky Browser Tests",
+            "--issue",
+            "Tests failed on slow CI runners",
+            "--resolution",
+            "Wait for network idle",
+            "--prevention",
+            "Never use fixed sleeps",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut child = Command::new("cargo")
+        .args(["run", "--", "learn", "--json", target_dir.to_str().unwrap()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            br#"{"title": "Stale Lockfile", "issue": "CI used old deps", "resolution": "Commit the lockfile", "prevention": ["Check lockfile in review"]}"#,
+        )
+        .unwrap();
+    assert!(child.wait_with_output().unwrap().status.success());
+
+    // Recording the same title again is a no-op
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "learn",
+            target_dir.to_str().unwrap(),
+            "--title",
+            "flaky browser tests",
+            "--issue",
+            "again",
+            "--resolution",
+            "again",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("already recorded"));
+
+    let learnings = fs::read_to_string(target_dir.join("docs").join("learnings.md")).unwrap();
+    assert!(
+        learnings
+            .contains("### 1. Flaky Browser Tests\n\n**Issue**: Tests failed on slow CI runners")
+    );
+    assert!(learnings.contains("**Proactive Prevention**:\n- Never use fixed sleeps"));
+    assert!(learnings.contains("### 2. Stale Lockfile"));
+    assert!(!learnings.contains("again"));
+}
+
+#[test]
+fn test_harvest_merges_project_learnings() {
+    let test_dir = setup_test_dir("harvest");
+    let library = test_dir.join("library.md");
+    fs::write(
+        &library,
+        "# Team Learnings\n\n### 1. Unused Imports\n\nRemove them.\n",
+    )
+    .unwrap();
+
+    for (project, title) in [("a", "Flaky Tests"), ("b", "Stale Lockfile")] {
+        let docs_dir = test_dir.join(project).join("docs");
+        fs::create_dir_all(&docs_dir).unwrap();
+        fs::write(
+            docs_dir.join("learnings.md"),
+            format!("# Learnings\n\n### 1. Unused Imports\n\nDup.\n\n### 2. {title}\n\nBody.\n"),
+        )
+        .unwrap();
+    }
+
+    let run = |extra: &[&str]| {
+        let mut args = vec![
+            "run",
+            "--",
+            "harvest",
+            "--library",
+            library.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra);
+        let a = test_dir.join("a");
+        let b = test_dir.join("b");
+        args.push(a.to_str().unwrap());
+        args.push(b.to_str().unwrap());
+        let output = Command::new("cargo")
+            .args(&args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            output.status.success(),
+            "Command failed: {:?}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    // --diff shows the review diff without writing
+    let stdout = run(&["--diff"]);
+    assert!(stdout.contains("+### 2. Flaky Tests"));
+    assert!(
+        !fs::read_to_string(&library)
+            .unwrap()
+            .contains("Flaky Tests")
+    );
+
+    run(&[]);
+    let merged = fs::read_to_string(&library).unwrap();
+    assert!(merged.contains("### 1. Unused Imports\n\nRemove them.\n"));
+    assert!(merged.contains("### 2. Flaky Tests"));
+    assert!(merged.contains("### 3. Stale Lockfile"));
+    assert!(!merged.contains("Dup."));
+
+    // Harvesting again adds nothing
+    let stdout = run(&[]);
+    assert!(stdout.contains("Unchanged"));
+}
+
+#[test]
+fn test_subcommands_update_show_clean() {
+    let test_dir = setup_test_dir("subcommands");
+    let target_dir = test_dir.join("project");
+    let docs_dir = target_dir.join("docs");
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(target_dir.join("Cargo.toml"), "[package]\nname = \"demo\"").unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        (
+            output.status.success(),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+        )
+    };
+    let target = target_dir.to_str().unwrap();
+
+    // update refuses to run before init
+    let (success, _) = run(&["update", target]);
+    assert!(!success);
+
+    let (success, _) = run(&["init", target]);
+    assert!(success);
+    let (success, _) = run(&["check", target]);
+    assert!(success);
+
+    fs::remove_file(docs_dir.join("tools.md")).unwrap();
+    let process = docs_dir.join("process.md");
+    fs::write(&process, "# Team notes\n").unwrap();
+
+    // update refreshes existing files but does not recreate deleted ones
+    let (success, _) = run(&["update", target]);
+    assert!(success);
+    assert!(!docs_dir.join("tools.md").exists());
+    assert!(
+        fs::read_to_string(&process)
+            .unwrap()
+            .contains("<!-- proact:begin")
+    );
+
+    let (success, stdout) = run(&["show", "quality", target]);
+    assert!(success);
+    assert!(stdout.contains("Quality-Oriented Development"));
+    assert!(!stdout.contains("Process-Oriented Workflow"));
+
+    let (success, stdout) = run(&["clean", target]);
+    assert!(success);
+    assert!(stdout.contains("Deleted"));
+    assert!(!docs_dir.join("ai_agent_instructions.md").exists());
+    assert_eq!(fs::read_to_string(&process).unwrap(), "# Team notes\n");
+}
+
+#[test]
+fn test_clean_removes_only_proact_content() {
+    let test_dir = setup_test_dir("clean_manifest");
+    let target_dir = test_dir.join("project");
+    let docs_dir = target_dir.join("docs");
+    fs::create_dir_all(&docs_dir).unwrap();
+    fs::write(target_dir.join("Cargo.toml"), "[package]\nname = \"demo\"").unwrap();
+    let claude = "# Our Claude notes\n";
+    fs::write(target_dir.join("CLAUDE.md"), claude).unwrap();
+    let learnings = "# Learnings\n\n### 1. Our Own Lesson\n\nKeep this.\n";
+    fs::write(docs_dir.join("learnings.md"), learnings).unwrap();
+    let mcp =
+        "{\n  \"mcpServers\": {\n    \"github\": {\n      \"command\": \"gh-mcp\"\n    }\n  }\n}\n";
+    fs::write(target_dir.join(".mcp.json"), mcp).unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        (
+            output.status.success(),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+        )
+    };
+    let target = target_dir.to_str().unwrap();
+
+    let (success, _) = run(&["init", target, "--agents", "claude,cursor", "--mcp"]);
+    assert!(success);
+    let manifest: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(target_dir.join(".proact/manifest.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(manifest["proact"]["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(manifest["command"], "init");
+    assert_eq!(manifest["project-types"], serde_json::json!(["rust"]));
+    assert_eq!(
+        manifest["options"]["agents"],
+        serde_json::json!(["claude", "cursor"])
+    );
+    let claude_record = manifest["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|record| record["path"] == "CLAUDE.md")
+        .unwrap();
+    assert_eq!(claude_record["action"], "appended");
+    assert_eq!(claude_record["ownership"], "managed");
+    assert!(
+        fs::read_to_string(docs_dir.join("learnings.md"))
+            .unwrap()
+            .contains("### 2.")
+    );
+    assert!(
+        fs::read_to_string(docs_dir.join("learnings.md"))
+            .unwrap()
+            .contains("### 2.")
+    );
+
+    // Edited after generation: the file is kept
+    fs::write(target_dir.join("COPYRIGHT"), "Copyright (c) Someone Else\n").unwrap();
+
+    let (success, stdout) = run(&["clean", "--dry-run", target]);
+    assert!(success);
+    assert!(stdout.contains("Would delete"));
+    assert!(target_dir.join("LICENSE").exists());
+
+    let (success, stdout) = run(&["clean", target]);
+    assert!(success);
+    assert!(stdout.contains("Kept"));
+    assert_eq!(
+        fs::read_to_string(target_dir.join("CLAUDE.md")).unwrap(),
+        claude
+    );
+    assert_eq!(
+        fs::read_to_string(docs_dir.join("learnings.md")).unwrap(),
+        learnings
+    );
+    assert_eq!(
+        fs::read_to_string(target_dir.join(".mcp.json")).unwrap(),
+        mcp
+    );
+    assert!(target_dir.join("COPYRIGHT").exists());
+    for removed in [
+        "LICENSE",
+        "docs/ai_agent_instructions.md",
+        "docs/process.md",
+        "opencode.json",
+        ".gemini",
+        ".cursor",
+        ".proact",
+    ] {
+        assert!(!target_dir.join(removed).exists(), "{removed} still exists");
+    }
+}
+
+#[test]
+fn test_project_config_file() {
+    let test_dir = setup_test_dir("project_config");
+
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(target_dir.join("team")).unwrap();
+    fs::write(target_dir.join("Cargo.toml"), "[package]\nname = \"demo\"").unwrap();
+    fs::write(
+        target_dir.join("team").join("review.md"),
+        "# Review checklist for {{project_name}}\n",
+    )
+    .unwrap();
+    fs::write(
+        target_dir.join("team").join("learnings.md"),
+        "# Acme Learnings\n",
+    )
+    .unwrap();
+    fs::write(
+        target_dir.join(".proact.toml"),
+        r#"
+output-dir = "agent-docs"
+sections = ["process", "summary"]
+templates = ["team/review.md"]
+learnings-library = "team/learnings.md"
+copyright-owner = "Acme Corp"
+agents = ["claude"]
+"#,
+    )
+    .unwrap();
+
+    // Isolate from the developer's user-level config
+    let config_home = test_dir.join("config-home");
+    let output = Command::new("cargo")
+        .args(["run", "--", target_dir.to_str().unwrap()])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env_remove("PROACT_C_OWNER")
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let docs_dir = target_dir.join("agent-docs");
+    let instructions = fs::read_to_string(docs_dir.join("ai_agent_instructions.md")).unwrap();
+    assert!(instructions.contains("Process-Oriented Workflow"));
+    assert!(!instructions.contains("Quality-Oriented Development"));
+
+    let learnings = fs::read_to_string(docs_dir.join("learnings.md")).unwrap();
+    assert!(learnings.contains("# Acme Learnings"));
+
+    let review = fs::read_to_string(docs_dir.join("review.md")).unwrap();
+    assert!(review.contains("# Review checklist for demo"));
+
+    let copyright = fs::read_to_string(target_dir.join("COPYRIGHT")).unwrap();
+    assert!(copyright.contains("Acme Corp"));
+
+    let claude = fs::read_to_string(target_dir.join("CLAUDE.md")).unwrap();
+    assert!(claude.contains("@agent-docs/ai_agent_instructions.md"));
+
+    // CLI flags override the config file
+    let output = Command::new("cargo")
+        .args(["run", "--", "-o", "cli-docs", target_dir.to_str().unwrap()])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(target_dir.join("cli-docs").join("process.md").exists());
+}
+
+#[test]
+fn test_diff_shows_changes_without_writing() {
+    let test_dir = setup_test_dir("diff_output");
+
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(target_dir.join("Cargo.toml"), "[package]").unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--diff", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("--- /dev/null\n+++ b/docs/ai_agent_instructions.md"),
+        "Diff should show the new instructions file: {stdout}"
+    );
+    assert!(stdout.contains("+++ b/COPYRIGHT"));
+    assert!(
+        !target_dir.join("docs").exists(),
+        "Diff mode should not create files"
+    );
+}
+
+#[test]
+fn test_check_detects_stale_docs() {
+    let test_dir = setup_test_dir("check_stale");
+
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(target_dir.join("Cargo.toml"), "[package]").unwrap();
+
+    // Nothing generated yet: check must fail without writing anything
+    let output = Command::new("cargo")
+        .args(["run", "--", "--check", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        !output.status.success(),
+        "Check should fail before generation"
+    );
+    assert!(
+        !target_dir.join("docs").exists(),
+        "Check mode should not create files"
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("missing"), "Should report missing files");
+
+    // Generate, then check passes
+    let output = Command::new("cargo")
+        .args(["run", "--", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--check", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "Check should pass after generation: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    // Edit inside the managed section: check fails and names the file
+    let process_path = target_dir.join("docs").join("process.md");
+    let process = fs::read_to_string(&process_path).unwrap();
+    fs::write(&process_path, process.replace("## Overview", "## Changed")).unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--check", target_dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("stale: {}", process_path.display())),
+        "Should report the stale file: {stdout}"
+    );
+}
+
+#[test]
+fn test_learnings_do_not_depend_on_working_directory() {
+    let test_dir = setup_test_dir("learnings_any_cwd");
 
     // Create a target project directory
     let target_dir = test_dir.join("proj");
@@ -306,7 +1017,7 @@ fn test_dry_run_skips_missing_learnings() {
     fs::write(target_dir.join("Cargo.toml"), "[package]").unwrap();
     let target_dir = fs::canonicalize(&target_dir).unwrap();
 
-    // Run CLI from a directory without docs/learnings.md
+    // Run CLI from a directory other than the proact checkout
     let manifest_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
     let output = Command::new("cargo")
         .args([
@@ -327,11 +1038,12 @@ fn test_dry_run_skips_missing_learnings() {
         String::from_utf8_lossy(&output.stderr)
     );
 
-    // Dry run should not mention learnings.md when source is missing
+    // Seed learnings are embedded in the binary, so they are generated
+    // regardless of the working directory
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(
-        !stdout.contains("learnings.md"),
-        "Output should not mention learnings.md when source is missing"
+        stdout.contains("Would create: ") && stdout.contains("learnings.md"),
+        "Output should mention learnings.md: {stdout}"
     );
 
     // Output directory should not be created
@@ -341,3 +1053,56 @@ fn test_dry_run_skips_missing_learnings() {
         "Output directory should not exist in dry-run mode"
     );
 }
+
+#[test]
+fn test_json_format_report() {
+    let test_dir = setup_test_dir("json_format");
+    let target_dir = test_dir.join("project");
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(
+        target_dir.join("Cargo.toml"),
+        "[package]\nname = \"demo\"\nlicense = \"GPL-3.0-only\"",
+    )
+    .unwrap();
+    let target = target_dir.to_str().unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .unwrap_or_else(|err| panic!("stdout is not a JSON report ({err})"));
+        (output.status.success(), report)
+    };
+
+    let (success, report) = run(&["check", "--format", "json", target]);
+    assert!(!success, "check should fail before generation");
+    assert_eq!(report["up-to-date"], false);
+
+    let (success, report) = run(&["--format", "json", target]);
+    assert!(success);
+    assert_eq!(report["command"], "init");
+    assert_eq!(report["dry-run"], false);
+    assert_eq!(report["project-types"], serde_json::json!(["rust"]));
+    assert_eq!(report["metadata"]["project-name"], "demo");
+    assert_eq!(report["files"][0]["action"], "created");
+    assert!(
+        report["warnings"][0]
+            .as_str()
+            .unwrap()
+            .contains("GPL-3.0-only")
+    );
+
+    let (success, report) = run(&["check", "--format", "json", target]);
+    assert!(success);
+    assert_eq!(report["up-to-date"], true);
+
+    let (success, report) = run(&["clean", "-n", "--format", "json", target]);
+    assert!(success);
+    assert_eq!(report["command"], "clean");
+    assert_eq!(report["files"][0]["action"], "deleted");
+    assert!(target_dir.join("docs").join("process.md").exists());
+}